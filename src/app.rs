@@ -1,17 +1,17 @@
-use crate::config::{Column, Config};
+use crate::config::{Column, Config, ConfirmLevel, GistColumn, GhqCheck, GhqLayout, ScanOptions};
 use crate::git::RepoStatus;
-use crate::{git, github, local};
+use crate::{git, github, local, platform};
 use anyhow::Result;
-use chrono::Local;
+use chrono::{DateTime, Local};
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 /// An entry in the error log
 #[derive(Debug, Clone)]
 pub struct ErrorLogEntry {
-    pub timestamp: String,
+    pub timestamp: DateTime<Local>,
     pub operation: String,
     pub error: String,
 }
@@ -19,7 +19,7 @@ pub struct ErrorLogEntry {
 impl ErrorLogEntry {
     pub fn new(operation: impl Into<String>, error: impl Into<String>) -> Self {
         Self {
-            timestamp: Local::now().format("%H:%M:%S").to_string(),
+            timestamp: Local::now(),
             operation: operation.into(),
             error: error.into(),
         }
@@ -30,6 +30,119 @@ impl ErrorLogEntry {
 pub enum ViewMode {
     Repos,
     Gists,
+    Starred,
+}
+
+impl ViewMode {
+    /// Views that list `RepoRow`s and share the repo actions
+    pub fn is_repo_list(&self) -> bool {
+        matches!(self, ViewMode::Repos | ViewMode::Starred)
+    }
+}
+
+/// Filter on local sync state, cycled from the summary in the title bar
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    Dirty,
+    Ahead,
+    Behind,
+    Diverged,
+}
+
+impl StatusFilter {
+    pub fn next(self) -> Self {
+        match self {
+            StatusFilter::All => StatusFilter::Dirty,
+            StatusFilter::Dirty => StatusFilter::Ahead,
+            StatusFilter::Ahead => StatusFilter::Behind,
+            StatusFilter::Behind => StatusFilter::Diverged,
+            StatusFilter::Diverged => StatusFilter::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StatusFilter::All => "all",
+            StatusFilter::Dirty => "dirty",
+            StatusFilter::Ahead => "ahead",
+            StatusFilter::Behind => "behind",
+            StatusFilter::Diverged => "diverged",
+        }
+    }
+
+    pub fn matches(self, status: Option<&RepoStatus>) -> bool {
+        match (self, status) {
+            (StatusFilter::All, _) => true,
+            (_, None) => false,
+            (StatusFilter::Dirty, Some(s)) => s.is_dirty(),
+            (StatusFilter::Ahead, Some(s)) => s.ahead > 0 && s.behind == 0,
+            (StatusFilter::Behind, Some(s)) => s.behind > 0 && s.ahead == 0,
+            (StatusFilter::Diverged, Some(s)) => s.ahead > 0 && s.behind > 0,
+        }
+    }
+
+    /// Whether any non-All filter would pick the repo out
+    pub fn needs_attention(status: Option<&RepoStatus>) -> bool {
+        status.map(|s| s.is_dirty() || s.ahead > 0 || s.behind > 0).unwrap_or(false)
+    }
+}
+
+/// Filter on repo type, so forks needing upstream syncing can be listed on their own
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TypeFilter {
+    #[default]
+    All,
+    Forks,
+    Sources,
+}
+
+impl TypeFilter {
+    pub fn next(self) -> Self {
+        match self {
+            TypeFilter::All => TypeFilter::Forks,
+            TypeFilter::Forks => TypeFilter::Sources,
+            TypeFilter::Sources => TypeFilter::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TypeFilter::All => "all",
+            TypeFilter::Forks => "forks",
+            TypeFilter::Sources => "sources",
+        }
+    }
+
+    pub fn matches(self, repo: &RepoRow, username: &Option<String>) -> bool {
+        match self {
+            TypeFilter::All => true,
+            TypeFilter::Forks => repo_type_sort_order(repo, username) == 2,
+            TypeFilter::Sources => repo_type_sort_order(repo, username) == 0,
+        }
+    }
+}
+
+/// Counts of repos needing attention, recomputed after each refresh
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StatusSummary {
+    pub dirty: usize,
+    pub ahead: usize,
+    pub behind: usize,
+    pub diverged: usize,
+}
+
+impl StatusSummary {
+    pub fn count(self, filter: StatusFilter) -> usize {
+        match filter {
+            StatusFilter::All => 0,
+            StatusFilter::Dirty => self.dirty,
+            StatusFilter::Ahead => self.ahead,
+            StatusFilter::Behind => self.behind,
+            StatusFilter::Diverged => self.diverged,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -43,7 +156,9 @@ pub enum SortColumn {
     Dirty,
     Private,
     Archived,
+    Starred,
     Ghq,
+    Note,
 }
 
 impl SortColumn {
@@ -85,7 +200,9 @@ impl SortColumn {
             SortColumn::Dirty => Column::Dirty,
             SortColumn::Private => Column::Private,
             SortColumn::Archived => Column::Archived,
+            SortColumn::Starred => Column::Starred,
             SortColumn::Ghq => Column::Ghq,
+            SortColumn::Note => Column::Note,
         }
     }
 
@@ -101,7 +218,9 @@ impl SortColumn {
             Column::Dirty => SortColumn::Dirty,
             Column::Private => SortColumn::Private,
             Column::Archived => SortColumn::Archived,
+            Column::Starred => SortColumn::Starred,
             Column::Ghq => SortColumn::Ghq,
+            Column::Note => SortColumn::Note,
         }
     }
 
@@ -117,7 +236,9 @@ impl SortColumn {
             "dirty" => SortColumn::Dirty,
             "private" | "priv" => SortColumn::Private,
             "archived" | "arch" => SortColumn::Archived,
+            "starred" | "star" => SortColumn::Starred,
             "ghq" => SortColumn::Ghq,
+            "note" => SortColumn::Note,
             _ => SortColumn::LastUpdated,
         }
     }
@@ -134,7 +255,73 @@ impl SortColumn {
             SortColumn::Dirty => "dirty",
             SortColumn::Private => "private",
             SortColumn::Archived => "archived",
+            SortColumn::Starred => "starred",
             SortColumn::Ghq => "ghq",
+            SortColumn::Note => "note",
+        }
+    }
+}
+
+/// Sortable columns of the gists table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GistSortColumn {
+    Description,
+    Files,
+    Public,
+    Status,
+    Updated,
+}
+
+impl GistSortColumn {
+    /// Get next sort column among the visible sortable columns
+    pub fn next(self, visible: &[GistColumn]) -> Self {
+        let sortable: Vec<Self> = visible.iter().filter_map(|&c| Self::from_column(c)).collect();
+        match sortable.iter().position(|&c| c == self) {
+            Some(idx) => sortable[(idx + 1) % sortable.len()],
+            None => sortable.first().copied().unwrap_or(self),
+        }
+    }
+
+    /// Get previous sort column among the visible sortable columns
+    pub fn prev(self, visible: &[GistColumn]) -> Self {
+        let sortable: Vec<Self> = visible.iter().filter_map(|&c| Self::from_column(c)).collect();
+        match sortable.iter().position(|&c| c == self) {
+            Some(idx) => sortable[(idx + sortable.len() - 1) % sortable.len()],
+            None => sortable.first().copied().unwrap_or(self),
+        }
+    }
+
+    /// Convert from GistColumn; Dirty and Path are not sortable
+    pub fn from_column(col: GistColumn) -> Option<Self> {
+        match col {
+            GistColumn::Description => Some(GistSortColumn::Description),
+            GistColumn::Files => Some(GistSortColumn::Files),
+            GistColumn::Public => Some(GistSortColumn::Public),
+            GistColumn::Status => Some(GistSortColumn::Status),
+            GistColumn::Updated => Some(GistSortColumn::Updated),
+            GistColumn::Dirty | GistColumn::Path => None,
+        }
+    }
+
+    /// Convert from config string
+    pub fn from_string(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "description" | "name" => GistSortColumn::Description,
+            "files" => GistSortColumn::Files,
+            "public" => GistSortColumn::Public,
+            "status" => GistSortColumn::Status,
+            _ => GistSortColumn::Updated,
+        }
+    }
+
+    /// Convert to config string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GistSortColumn::Description => "description",
+            GistSortColumn::Files => "files",
+            GistSortColumn::Public => "public",
+            GistSortColumn::Status => "status",
+            GistSortColumn::Updated => "updated",
         }
     }
 }
@@ -144,6 +331,10 @@ pub enum InputMode {
     Normal,
     ConfirmDelete,
     UploadForm,
+    Commit,
+    Note,
+    NewBranch,
+    Search,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -153,6 +344,64 @@ pub enum DeleteType {
     Gist,
 }
 
+/// How a confirmation dialog is answered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfirmKind {
+    YesNo, // y/Enter confirms, Esc/n cancels
+    Typed, // Answer is typed, then submitted with Enter
+}
+
+/// Prompt for a destructive action, drawn by `ui::draw_confirm_dialog`
+#[derive(Debug, Clone)]
+pub struct ConfirmDialog {
+    pub title: String,
+    pub message: Vec<String>,
+    pub kind: ConfirmKind,
+    pub expected: Option<String>, // Answer a typed dialog needs; None accepts y/yes
+}
+
+impl ConfirmDialog {
+    pub fn yes_no(title: impl Into<String>, message: Vec<String>) -> Self {
+        Self { title: title.into(), message, kind: ConfirmKind::YesNo, expected: None }
+    }
+
+    pub fn typed(title: impl Into<String>, message: Vec<String>, expected: Option<String>) -> Self {
+        Self { title: title.into(), message, kind: ConfirmKind::Typed, expected }
+    }
+
+    /// Prompt shown above the input of a typed dialog
+    pub fn prompt(&self) -> String {
+        match &self.expected {
+            Some(expected) => format!("Type '{}' to confirm:", expected),
+            None => "Type 'y' or 'yes' to confirm:".to_string(),
+        }
+    }
+
+    /// Whether a typed answer confirms the action
+    pub fn accepts(&self, answer: &str) -> bool {
+        let answer = answer.trim();
+        match &self.expected {
+            Some(expected) => answer.eq_ignore_ascii_case(expected),
+            None => answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes"),
+        }
+    }
+}
+
+/// Action waiting on a y/Enter confirmation popup
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmAction {
+    Relink { name: String, path: String, url: String },
+    ToggleVisibility { id: String },
+    ToggleArchive { id: String },
+    UnshallowThenQuicksync { id: String },
+    RenameFolder { name: String, from: String, to: String },
+    Reorganize { name: String, from: String, to: String },
+    DeleteFolder { id: String },
+    ForceDeleteBranch { name: String, path: String, branch: String },
+    RebaseFork { name: String, path: String, parent: String, branch: String },
+    Quit,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum PopupType {
     Help,
@@ -160,6 +409,14 @@ pub enum PopupType {
     Ignored,
     Upload,
     Errors,
+    Files,
+    Confirm,
+    Log,
+    Copy,
+    ForkDiff,
+    Path,
+    Branches,
+    DiskUsage,
 }
 
 /// Fields in the upload form
@@ -203,12 +460,43 @@ pub struct UploadFormState {
     pub local_path: String,       // Path to upload from
 }
 
+impl UploadFormState {
+    /// Why GitHub would reject the name, checked as the user types
+    pub fn name_error(&self) -> Option<&'static str> {
+        let name = self.name.as_str();
+        if name.is_empty() {
+            Some("name is required")
+        } else if name.starts_with('.') {
+            Some("name can't start with a dot")
+        } else if name.len() > 100 {
+            Some("name is longer than 100 characters")
+        } else if !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) {
+            Some("only letters, digits, - _ and . are allowed")
+        } else {
+            None
+        }
+    }
+}
+
+/// State for the commit message input
+#[derive(Debug, Clone)]
+pub struct CommitFormState {
+    pub message: String,
+    pub amend: bool,
+    pub can_amend: bool,    // False when the repo has no commits yet
+    pub stage_all: bool,    // False when committing only files staged in the files popup
+    pub push: bool,         // Push once the commit succeeds (never with amend)
+    pub repo_name: String,
+    pub local_path: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct Popup {
     pub popup_type: PopupType,
     pub scroll: usize,
     pub content: Vec<String>,
     pub selected: usize,
+    pub filter: Option<String>, // Help: incremental filter, Some while typing after /
 }
 
 impl Popup {
@@ -218,11 +506,22 @@ impl Popup {
             scroll: 0,
             content,
             selected: 0,
+            filter: None,
+        }
+    }
+
+    /// Lines to render: Help narrowed by the filter, otherwise all content
+    pub fn visible_lines(&self) -> Vec<&String> {
+        match self.filter.as_deref() {
+            Some(query) if !query.is_empty() && self.popup_type == PopupType::Help => {
+                filter_help_lines(&self.content, query)
+            }
+            _ => self.content.iter().collect(),
         }
     }
 
     pub fn scroll_down(&mut self, visible_lines: usize) {
-        let max_scroll = self.content.len().saturating_sub(visible_lines);
+        let max_scroll = self.visible_lines().len().saturating_sub(visible_lines);
         self.scroll = (self.scroll + 1).min(max_scroll);
     }
 
@@ -231,13 +530,36 @@ impl Popup {
     }
 }
 
+/// A scan root as given, resolved once so ghq checks don't hit the filesystem for every row
 #[derive(Debug, Clone)]
+pub struct ScanRoot {
+    pub path: String,
+    pub canonical: PathBuf, // Symlinks resolved; the given path when it doesn't exist
+}
+
+impl ScanRoot {
+    pub fn resolve_all(roots: &[String]) -> Vec<ScanRoot> {
+        roots
+            .iter()
+            .map(|path| ScanRoot {
+                path: path.clone(),
+                canonical: resolve_path(path),
+            })
+            .collect()
+    }
+}
+
+/// Symlinks resolved; the given path when it doesn't exist
+fn resolve_path(path: &str) -> PathBuf {
+    Path::new(path).canonicalize().unwrap_or_else(|_| PathBuf::from(path))
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct RepoRow {
     pub id: String,
     pub owner: Option<String>,
     pub name: String,
     pub github_url: Option<String>,
-    #[allow(dead_code)]
     pub ssh_url: Option<String>,
     pub is_fork: bool,
     pub fork_parent: Option<String>,
@@ -245,13 +567,22 @@ pub struct RepoRow {
     pub is_archived: bool,
     pub is_member: bool, // User owns or is member of org
     pub local_path: Option<String>,
+    pub local_canonical: Option<PathBuf>, // local_path resolved once at refresh, for ghq checks
     pub git_status: Option<RepoStatus>,
     pub last_commit_time: Option<i64>, // Unix timestamp
+    pub last_commit_hash: Option<String>, // Short hash of the local HEAD commit
     pub is_subrepo: bool,              // Nested inside another repo
     pub parent_repo: Option<String>,   // Path to parent repo if subrepo
     pub fork_ahead: Option<u32>,       // Commits ahead of upstream (for forks)
     pub fork_behind: Option<u32>,      // Commits behind upstream (for forks)
     pub has_git: bool,                 // Whether this folder has a git repo
+    pub is_empty: bool,                // GitHub repo has no commits yet
+    pub is_shallow: bool,              // Local clone has truncated history
+    pub is_watched: bool,              // Subscribed to GitHub notifications
+    pub is_starred: bool,              // Starred by the viewer
+    pub default_branch: Option<String>,        // GitHub default branch
+    pub parent_default_branch: Option<String>, // Upstream default branch (for forks)
+    pub note: Option<String>,                  // User note from config, re-applied after each refresh
 }
 
 impl RepoRow {
@@ -267,19 +598,63 @@ impl RepoRow {
         self.github_url.is_some() && self.local_path.is_none()
     }
 
+    /// Path the local folder would have if renamed to the GitHub repo name,
+    /// when the two differ (subrepos and local-only folders never qualify)
+    pub fn renamed_folder_path(&self) -> Option<PathBuf> {
+        if self.github_url.is_none() || self.is_subrepo {
+            return None;
+        }
+        let local = Path::new(self.local_path.as_ref()?);
+        let folder = local.file_name()?.to_string_lossy();
+        if folder == self.name.as_str() {
+            None
+        } else {
+            Some(local.with_file_name(&self.name))
+        }
+    }
+
+    /// Default branch from GitHub, falling back to the local origin/HEAD
+    pub fn default_branch_name(&self) -> Option<&str> {
+        self.default_branch
+            .as_deref()
+            .or_else(|| self.git_status.as_ref().and_then(|s| s.default_branch.as_deref()))
+    }
+
+    /// True when the local checkout is on a branch other than the default
+    pub fn is_off_default_branch(&self) -> bool {
+        match (&self.git_status, self.default_branch_name()) {
+            (Some(status), Some(default)) => status.has_commits && status.branch != default,
+            _ => false,
+        }
+    }
+
     pub fn fork_owner(&self) -> Option<&str> {
         self.fork_parent.as_ref().and_then(|p| p.split('/').next())
     }
 
+    /// The scan root this repo's clone lives under; remote-only repos use the first (primary) root
+    pub fn root_for<'a>(&self, roots: &'a [ScanRoot]) -> Option<&'a ScanRoot> {
+        let primary = roots.first();
+        let local = match self.local_path {
+            Some(ref p) => Path::new(p),
+            None => return primary,
+        };
+        let local_canonical = self.local_canonical.as_deref().unwrap_or(local);
+        roots
+            .iter()
+            .filter(|root| local.starts_with(&root.path) || local_canonical.starts_with(&root.canonical))
+            // Nested roots: the deepest one owns the clone
+            .max_by_key(|root| root.path.len())
+            .or(primary)
+    }
+
     /// Returns the expected ghq-style path for this repo
-    pub fn expected_ghq_path(&self, local_root: &str) -> Option<String> {
+    pub fn expected_ghq_path(&self, roots: &[ScanRoot], layout: GhqLayout) -> Option<String> {
         if let Some(ref owner) = self.owner {
-            // Canonicalize local_root to get consistent path
-            let root = std::path::Path::new(local_root)
-                .canonicalize()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|_| local_root.trim_end_matches('/').to_string());
-            Some(format!("{}/github.com/{}/{}", root, owner, self.name))
+            // The canonical root gives a consistent path
+            let root = &self.root_for(roots)?.canonical;
+            let path = root.join(layout.relative_path("github.com", owner, &self.name));
+            Some(path.to_string_lossy().to_string())
         } else {
             None
         }
@@ -287,7 +662,7 @@ impl RepoRow {
 
     /// Checks if the current local path follows ghq convention
     /// Subrepos are always considered to follow ghq (they're nested in their parent)
-    pub fn follows_ghq(&self, local_root: &str) -> Option<bool> {
+    pub fn follows_ghq(&self, roots: &[ScanRoot], check: GhqCheck) -> Option<bool> {
         // Subrepos are always considered as following ghq - they're nested inside
         // their parent repo which should be organized correctly
         if self.is_subrepo {
@@ -295,31 +670,33 @@ impl RepoRow {
         }
 
         if let (Some(ref local_path), Some(ref owner)) = (&self.local_path, &self.owner) {
-            // Check if path matches pattern: {root}/github.com/{owner}/{name}
-            // Use case-insensitive comparison and resolve symlinks
-            let local = std::path::Path::new(local_path);
-
-            // Canonicalize the actual local path
-            let path_to_check = match local.canonicalize() {
-                Ok(p) => p.to_string_lossy().to_string(),
-                Err(_) => local_path.clone(),
+            // Check if path matches the layout, e.g. {root}/github.com/{owner}/{name}
+            // Resolve symlinks; case-insensitive unless strict_case is set
+            let local = Path::new(local_path);
+            let root = match self.root_for(roots) {
+                Some(root) => root,
+                None => return Some(false), // No scan roots at all
             };
 
-            // Canonicalize local_root
-            let root_canonical = match std::path::Path::new(local_root).canonicalize() {
-                Ok(p) => p.to_string_lossy().to_string(),
-                Err(_) => local_root.trim_end_matches('/').to_string(),
+            // Only the existing ends are canonicalized (at refresh); the expected path may not exist yet.
+            // Missing paths (or a root that isn't an ancestor once resolved) fall back to raw.
+            let local_canonical = self.local_canonical.as_deref().unwrap_or(local);
+            let relative = local_canonical.strip_prefix(&root.canonical)
+                .or_else(|_| local.strip_prefix(&root.path));
+            let relative = match relative {
+                Ok(r) => r,
+                Err(_) => return Some(false), // Outside the scan root
             };
 
-            // Build expected path and canonicalize it too
-            let expected_raw = format!("{}/github.com/{}/{}", root_canonical, owner, self.name);
-            let expected = std::path::Path::new(&expected_raw)
-                .canonicalize()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or(expected_raw);
-
-            // Compare paths (case-insensitive for owner/name on case-insensitive filesystems)
-            Some(path_to_check.eq_ignore_ascii_case(&expected) || path_to_check == expected)
+            // Compare github.com/owner/name segment by segment
+            let expected = check.layout.relative_path("github.com", owner, &self.name);
+            let actual: Vec<_> = relative.components().collect();
+            let wanted: Vec<_> = expected.components().collect();
+            Some(actual.len() == wanted.len() && actual.iter().zip(&wanted).all(|(a, w)| {
+                let (a, w) = (a.as_os_str(), w.as_os_str());
+                // Case-insensitive unless strict_case flags paths differing only in case
+                a == w || (!check.strict_case && a.to_string_lossy().eq_ignore_ascii_case(&w.to_string_lossy()))
+            }))
         } else {
             None // No local path or no GitHub info
         }
@@ -337,7 +714,6 @@ pub struct GistRow {
     pub git_status: Option<RepoStatus>,
     #[allow(dead_code)]
     pub created_at: Option<String>,
-    #[allow(dead_code)]
     pub updated_at: Option<String>,
 }
 
@@ -354,20 +730,46 @@ impl GistRow {
 /// Braille spinner frames
 pub const SPINNER_FRAMES: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
 
+/// Parallel `git fetch` processes for the startup fetch
+const STARTUP_FETCH_CONCURRENCY: usize = 8;
+
+/// Number of commits shown in the log popup
+const LOG_COMMIT_COUNT: usize = 50;
+
+/// Number of recent commits checked for "authored by me" in details
+const AUTHORSHIP_COMMIT_COUNT: usize = 20;
+
+/// Largest clones listed in the disk usage popup
+const DISK_USAGE_TOP: usize = 15;
+
+/// Window for the second g of gg
+const GG_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Size of one local clone, measured for the disk usage popup
+#[derive(Debug, Clone)]
+pub struct CloneSize {
+    pub name: String,
+    pub path: String,
+    pub bytes: u64,
+}
+
 /// Cached GitHub data to avoid re-fetching for local-only operations
 pub struct GitHubCache {
     pub repos: Vec<github::GitHubRepoInfo>,
     pub gists: Vec<GistRow>,
+    pub starred: Vec<github::GitHubRepoInfo>,
 }
 
 pub struct App {
-    pub local_root: String,
+    pub local_roots: Vec<String>, // Scan roots; new clones and gists go under the first
+    pub scan_roots: Vec<ScanRoot>, // local_roots resolved once at startup
     pub view_mode: ViewMode,
     pub github_username: Option<String>,
 
     // Data
     pub repos: Vec<RepoRow>,
     pub gists: Vec<GistRow>,
+    pub starred: Vec<RepoRow>,
 
     // Configuration (includes ignored_repos, columns, etc.)
     pub config: Config,
@@ -377,14 +779,28 @@ pub struct App {
     pub scroll_offset: usize,
     pub sort_column: SortColumn,
     pub sort_ascending: bool,
+    pub gist_sort_column: GistSortColumn,
+    pub gist_sort_ascending: bool,
     pub show_archived: bool,
     pub show_private: bool,
-
-    // Marked items for batch operations (stores repo/gist IDs)
-    pub marked: HashSet<String>,
+    pub status_filter: StatusFilter,
+    pub type_filter: TypeFilter,
+    pub remote_only: bool, // Only repos without a local clone
+    pub notes_only: bool,  // Only repos with a note
+    pub search_query: String, // Case-insensitive substring filter typed after /
+    pub pending_g: Option<Instant>, // First g of a gg jump-to-top sequence
+    pub show_commit_hash: bool, // Updated column shows the last commit hash instead of its age
+    pub dry_run: bool,          // Report mutating actions instead of running them
+    pub status_summary: StatusSummary,
+    pub column_widths: HashMap<Column, u16>, // Auto-fit overrides; empty uses defaults
+
+    // Marked items for batch operations, kept per view so switching views preserves them
+    pub marked_repos: HashSet<String>,
+    pub marked_gists: HashSet<String>,
 
     // Column selection for reordering (index into visible columns)
     pub selected_column: usize,
+    pub selected_gist_column: usize,
 
     // UI state
     pub status_message: Option<String>,
@@ -393,9 +809,14 @@ pub struct App {
     pub status_is_error: bool,   // true = show error (red, persistent)
     pub input_mode: InputMode,
     pub popup: Option<Popup>,
+    pub help_scroll: usize, // Help popup scroll restored on reopen (this session)
+    pub startup_fetch_pending: bool, // fetch_on_startup runs once the first load lands
     pub input_buffer: String,
+    pub note_target: Option<String>, // Repo ID whose note is being edited
+    pub branch_target: Option<(String, String)>, // (name, path) of the repo getting a new branch
     pub confirm_buffer: String,
     pub pending_delete: Option<DeleteType>,
+    pub pending_delete_id: Option<String>, // Repo/gist ID captured when the confirm opened
 
     // Table area for mouse click detection (y offset, height)
     pub table_area: Option<(u16, u16)>,
@@ -406,8 +827,20 @@ pub struct App {
     // Background task communication
     pub task_rx: mpsc::Receiver<TaskResult>,
     pub task_tx: mpsc::Sender<TaskResult>,
+    pub update_rx: mpsc::Receiver<AppUpdate>, // Results of git lookups behind popups
+    pub update_tx: mpsc::Sender<AppUpdate>,
     pub refresh_rx: mpsc::Receiver<RefreshData>,
     pub refresh_tx: mpsc::Sender<RefreshData>,
+    pub progress_rx: mpsc::Receiver<ProgressUpdate>,
+    pub progress_tx: mpsc::Sender<ProgressUpdate>,
+    pub config_rx: mpsc::Receiver<()>, // Signals external edits to config.toml
+    pub config_tx: mpsc::Sender<()>,
+    pub disk_usage_rx: mpsc::Receiver<(u64, Vec<CloneSize>)>,
+    pub disk_usage_tx: mpsc::Sender<(u64, Vec<CloneSize>)>,
+    pub disk_usage: Option<Vec<CloneSize>>, // Cached until the next refresh, largest first
+    pub disk_usage_generation: u64, // Bumped on refresh so in-flight measurements are dropped
+    pub measuring_disk_usage: bool,
+    pub progress: HashMap<String, u8>, // Latest percentage per running operation
     pub pending_refresh: bool,       // Full refresh (clears cache)
     pub pending_local_refresh: bool, // Local-only refresh (uses cache)
 
@@ -417,6 +850,21 @@ pub struct App {
     // Upload form state
     pub upload_form: Option<UploadFormState>,
 
+    // Commit input state
+    pub commit_form: Option<CommitFormState>,
+
+    // Action shown in the confirm popup, run on y/Enter
+    pub pending_confirm: Option<ConfirmAction>,
+    pub confirm_dialog: Option<ConfirmDialog>, // Text of the open confirm popup or typed delete
+
+    // Files popup state: repo (name, path) and its changed files
+    pub files_repo: Option<(String, String)>,
+    pub file_changes: Vec<git::FileChange>,
+
+    // Branch list state (repo name and path, plus the listed branches)
+    pub branches_repo: Option<(String, String)>,
+    pub branches: Vec<git::Branch>,
+
     // Error log for viewing after quit
     pub error_log: Vec<ErrorLogEntry>,
 }
@@ -430,27 +878,89 @@ pub struct TaskResult {
     pub invalidates_github_cache: bool,  // If true, needs full refresh; if false, local-only refresh
 }
 
+/// Applies what a background lookup found; run by the main loop so key handlers never wait on git
+pub type AppUpdate = Box<dyn FnOnce(&mut App) + Send>;
+
+/// Progress from a running background task (clone/fetch/pull percentage)
+pub struct ProgressUpdate {
+    pub operation: String, // Same key as the task's TaskResult operation
+    pub percent: u8,
+}
+
 /// Data loaded from a refresh operation
 pub struct RefreshData {
     pub github_username: Option<String>,
     pub repos: Vec<RepoRow>,
     pub gists: Vec<GistRow>,
+    pub starred: Vec<RepoRow>,
     pub error: Option<String>,                      // Error message to display in status bar
+    pub errors: Vec<ErrorLogEntry>,                 // Per-repo problems for the error log
     pub github_cache: Option<GitHubCache>,          // Cache to store for local-only refreshes
 }
 
+/// How often config.toml is checked for external edits
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Poll the config file's modification time and signal whenever it changes
+async fn watch_config(tx: mpsc::Sender<()>) {
+    let path = Config::config_path();
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let mut last = modified(&path);
+    let mut interval = tokio::time::interval(CONFIG_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let current = modified(&path);
+        if current != last {
+            last = current;
+            if tx.send(()).await.is_err() {
+                break; // App has shut down
+            }
+        }
+    }
+}
+
+/// Discover local repos; a missing git binary is reported once instead of per repo
+async fn scan_local(local_roots: &[String], scan: &ScanOptions) -> (Vec<local::LocalRepo>, Vec<ErrorLogEntry>, Option<String>) {
+    let git_error = git::check_installed().await.err().map(|e| e.to_string());
+    let mut local_repos = Vec::new();
+    let mut seen = HashSet::new();
+    for root in local_roots {
+        // Nested roots would otherwise list the inner repos twice
+        let found = local::discover_repos(root, scan).await.unwrap_or_default();
+        local_repos.extend(found.into_iter().filter(|r| seen.insert(r.path.clone())));
+    }
+    let errors = if git_error.is_some() {
+        Vec::new()
+    } else {
+        local_repos
+            .iter()
+            .filter_map(|r| {
+                r.status_error
+                    .as_ref()
+                    .map(|e| ErrorLogEntry::new(format!("status {}", r.name), e.clone()))
+            })
+            .collect()
+    };
+    (local_repos, errors, git_error)
+}
+
 /// Perform a full data refresh (runs in background task)
-async fn perform_refresh(local_root: String) -> RefreshData {
+async fn perform_refresh(local_roots: Vec<String>, scan: ScanOptions) -> RefreshData {
     // Check gh authentication first
     if let Err(e) = github::check_auth().await {
         // Still discover local repos even without GitHub auth
-        let local_repos = local::discover_repos(&local_root).await.unwrap_or_default();
+        let (local_repos, errors, git_error) = scan_local(&local_roots, &scan).await;
         let repos = merge_repos(Vec::new(), local_repos);
         return RefreshData {
             github_username: None,
             repos,
             gists: Vec::new(),
-            error: Some(e.to_string()),
+            starred: Vec::new(),
+            error: Some(match git_error {
+                Some(git_error) => format!("{}; {}", git_error, e),
+                None => e.to_string(),
+            }),
+            errors,
             github_cache: None,
         };
     }
@@ -464,14 +974,26 @@ async fn perform_refresh(local_root: String) -> RefreshData {
     // Fetch fork comparison data (commits ahead/behind upstream)
     github::fetch_fork_comparisons(&mut github_repos).await;
 
+    // Fetch starred repos
+    let mut starred_repos = github::fetch_starred_repos().await.unwrap_or_default();
+
+    // Mark watched repos
+    let watched = github::fetch_watched_repos().await.unwrap_or_default();
+    github::apply_watched(&mut github_repos, &watched);
+    github::apply_watched(&mut starred_repos, &watched);
+
     // Discover local repos
-    let local_repos = local::discover_repos(&local_root).await.unwrap_or_default();
+    let (local_repos, errors, git_error) = scan_local(&local_roots, &scan).await;
 
     // Merge into unified list
     let repos = merge_repos(github_repos.clone(), local_repos);
 
     // Fetch gists
-    let gists = github::fetch_gists_as_rows(&local_root).await.unwrap_or_default();
+    let primary_root = local_roots.first().map(String::as_str).unwrap_or_default();
+    let gists = github::fetch_gists_as_rows(primary_root).await.unwrap_or_default();
+
+    // Reuse merged rows for starred repos so local clones are detected
+    let starred = starred_rows(&starred_repos, &repos);
 
     RefreshData {
         github_username,
@@ -479,88 +1001,210 @@ async fn perform_refresh(local_root: String) -> RefreshData {
         github_cache: Some(GitHubCache {
             repos: github_repos,
             gists: gists.clone(),
+            starred: starred_repos,
         }),
         gists,
-        error: None,
+        starred,
+        error: git_error,
+        errors,
+    }
+}
+
+/// Build a reviewable shell script that moves non-ghq repos into place
+pub async fn ghq_migration_script(local_roots: &[String]) -> Result<String> {
+    let config = Config::load();
+    github::check_auth().await?;
+    let github_repos = github::fetch_all_repos_graphql().await?;
+    let (local_repos, _, git_error) = scan_local(local_roots, &config.scan_options()).await;
+    if let Some(e) = git_error {
+        anyhow::bail!(e);
+    }
+    let mut repos = merge_repos(github_repos, local_repos);
+    repos.sort_by(|a, b| a.local_path.cmp(&b.local_path));
+    Ok(build_ghq_script(&repos, local_roots, &config))
+}
+
+/// Emit mkdir/mv lines for each non-compliant repo; moves onto existing paths are commented out
+fn build_ghq_script(repos: &[RepoRow], local_roots: &[String], config: &Config) -> String {
+    let mut lines = vec![
+        "#!/bin/sh".to_string(),
+        format!("# Move repos under {} into the ghq layout (generated by ghall)", local_roots.join(", ")),
+        "set -e".to_string(),
+    ];
+    let mut skipped = 0;
+    let roots = ScanRoot::resolve_all(local_roots);
+    for repo in repos {
+        if repo.follows_ghq(&roots, config.ghq_check()) != Some(false) {
+            continue;
+        }
+        if let (Some(from), Some(owner), Some(to)) = (&repo.local_path, &repo.owner, repo.expected_ghq_path(&roots, config.ghq_layout)) {
+            let dest = Path::new(&to);
+            let parent = dest.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+            let commands = [
+                format!("mkdir -p {}", shell_quote(&parent)),
+                format!("mv {} {}", shell_quote(from), shell_quote(&to)),
+            ];
+            lines.push(String::new());
+            if dest.exists() {
+                skipped += 1;
+                lines.push(format!("# {}/{}: destination already exists, skipped", owner, repo.name));
+                lines.extend(commands.iter().map(|c| format!("# {}", c)));
+            } else {
+                lines.push(format!("# {}/{}", owner, repo.name));
+                lines.extend(commands);
+            }
+        }
     }
+    if lines.len() == 3 {
+        lines.push(String::new());
+        lines.push("# All repos already follow the ghq layout".to_string());
+    } else if skipped > 0 {
+        lines.push(String::new());
+        lines.push(format!("# {} repo(s) skipped because their destination exists", skipped));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Quote a path for POSIX sh
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
 }
 
 /// Perform a local-only refresh using cached GitHub data (runs in background task)
-async fn perform_local_refresh(local_root: String, cache: GitHubCache) -> RefreshData {
+async fn perform_local_refresh(local_roots: Vec<String>, scan: ScanOptions, cache: GitHubCache) -> RefreshData {
     // Discover local repos
-    let local_repos = local::discover_repos(&local_root).await.unwrap_or_default();
+    let (local_repos, errors, git_error) = scan_local(&local_roots, &scan).await;
 
     // Merge with cached GitHub data
     let repos = merge_repos(cache.repos.clone(), local_repos);
+    let starred = starred_rows(&cache.starred, &repos);
 
     RefreshData {
         github_username: None, // Keep existing, don't update
         repos,
         gists: cache.gists.clone(),
-        error: None,
+        starred,
+        error: git_error,
+        errors,
         github_cache: Some(cache), // Preserve the cache
     }
 }
 
 impl App {
-    pub fn new(local_root: String) -> Result<Self> {
+    pub fn new(local_roots: Vec<String>) -> Result<Self> {
         // Load config from XDG config
-        let config = Config::load();
+        let app = Self::with_config(local_roots.clone(), Config::load());
+
+        // Spawn initial refresh in background
+        let refresh_tx = app.refresh_tx.clone();
+        let scan = app.config.scan_options();
+        tokio::spawn(async move {
+            let refresh_data = perform_refresh(local_roots, scan).await;
+            let _ = refresh_tx.send(refresh_data).await;
+        });
+
+        // Pick up edits made to config.toml outside the TUI
+        tokio::spawn(watch_config(app.config_tx.clone()));
+
+        Ok(app)
+    }
 
+    /// Build app state from a config without starting any background work
+    fn with_config(local_roots: Vec<String>, config: Config) -> Self {
         // Create channel for background task results
         let (task_tx, task_rx) = mpsc::channel(32);
+        let (update_tx, update_rx) = mpsc::channel(8);
         let (refresh_tx, refresh_rx) = mpsc::channel(1);
+        let (progress_tx, progress_rx) = mpsc::channel(32);
+        let (config_tx, config_rx) = mpsc::channel(1);
+        let (disk_usage_tx, disk_usage_rx) = mpsc::channel(1);
 
         // Initialize settings from config
         let sort_column = SortColumn::from_string(&config.sort_column);
         let sort_ascending = config.sort_ascending;
+        let gist_sort_column = GistSortColumn::from_string(&config.gist_sort_column);
+        let gist_sort_ascending = config.gist_sort_ascending;
         let show_archived = config.show_archived;
         let show_private = config.show_private;
+        let fetch_on_startup = config.fetch_on_startup;
+        let scan_roots = ScanRoot::resolve_all(&local_roots);
 
-        let app = Self {
-            local_root: local_root.clone(),
+        Self {
+            local_roots,
+            scan_roots,
             view_mode: ViewMode::Repos,
             github_username: None, // Will be fetched during first refresh
             repos: Vec::new(),
             gists: Vec::new(),
+            starred: Vec::new(),
             config,
             selected: 0,
             scroll_offset: 0,
             sort_column,
             sort_ascending,
+            gist_sort_column,
+            gist_sort_ascending,
             show_archived,
             show_private,
-            marked: HashSet::new(),
+            status_filter: StatusFilter::All,
+            type_filter: TypeFilter::All,
+            remote_only: false,
+            notes_only: false,
+            search_query: String::new(),
+            pending_g: None,
+            show_commit_hash: false,
+            dry_run: false,
+            status_summary: StatusSummary::default(),
+            column_widths: HashMap::new(),
+            marked_repos: HashSet::new(),
+            marked_gists: HashSet::new(),
             selected_column: 0,
+            selected_gist_column: 0,
             status_message: Some("Loading...".to_string()),
             status_time: Some(Instant::now()),
             status_is_loading: true,
             status_is_error: false,
             input_mode: InputMode::Normal,
             popup: None,
+            help_scroll: 0,
+            startup_fetch_pending: fetch_on_startup,
             input_buffer: String::new(),
+            note_target: None,
+            branch_target: None,
             confirm_buffer: String::new(),
             pending_delete: None,
+            pending_delete_id: None,
             table_area: None,
             spinner_frame: 0,
             task_rx,
             task_tx,
+            update_rx,
+            update_tx,
             refresh_rx,
-            refresh_tx: refresh_tx.clone(),
+            refresh_tx,
+            progress_rx,
+            progress_tx,
+            config_rx,
+            config_tx,
+            disk_usage_rx,
+            disk_usage_tx,
+            disk_usage: None,
+            disk_usage_generation: 0,
+            measuring_disk_usage: false,
+            progress: HashMap::new(),
             pending_refresh: false,
             pending_local_refresh: false,
             github_cache: None,
             upload_form: None,
+            commit_form: None,
+            pending_confirm: None,
+            confirm_dialog: None,
+            files_repo: None,
+            file_changes: Vec::new(),
+            branches_repo: None,
+            branches: Vec::new(),
             error_log: Vec::new(),
-        };
-
-        // Spawn initial refresh in background
-        tokio::spawn(async move {
-            let refresh_data = perform_refresh(local_root).await;
-            let _ = refresh_tx.send(refresh_data).await;
-        });
-
-        Ok(app)
+        }
     }
 
     // Check if current user can modify repo visibility
@@ -569,15 +1213,21 @@ impl App {
         repo.github_url.is_some() && repo.is_member
     }
 
+    /// First scan root, where new clones and gists are placed
+    pub fn primary_root(&self) -> &str {
+        self.local_roots.first().map(String::as_str).unwrap_or_default()
+    }
+
     /// Trigger a full background refresh (non-blocking, clears cache)
     pub fn trigger_refresh(&mut self) {
         self.set_status("Refreshing...");
         self.github_cache = None; // Clear cache for full refresh
-        let local_root = self.local_root.clone();
+        let local_roots = self.local_roots.clone();
+        let scan = self.config.scan_options();
         let tx = self.refresh_tx.clone();
 
         tokio::spawn(async move {
-            let refresh_data = perform_refresh(local_root).await;
+            let refresh_data = perform_refresh(local_roots, scan).await;
             let _ = tx.send(refresh_data).await;
         });
     }
@@ -587,11 +1237,12 @@ impl App {
     pub fn trigger_local_refresh(&mut self) {
         if let Some(cache) = self.github_cache.take() {
             self.set_status("Updating...");
-            let local_root = self.local_root.clone();
+            let local_roots = self.local_roots.clone();
+            let scan = self.config.scan_options();
             let tx = self.refresh_tx.clone();
 
             tokio::spawn(async move {
-                let refresh_data = perform_local_refresh(local_root, cache).await;
+                let refresh_data = perform_local_refresh(local_roots, scan, cache).await;
                 let _ = tx.send(refresh_data).await;
             });
         } else {
@@ -600,16 +1251,22 @@ impl App {
         }
     }
 
+    /// Rotate Repos → Gists → Starred; repo and gist marks are kept separately
     pub fn toggle_view_mode(&mut self) {
         self.view_mode = match self.view_mode {
             ViewMode::Repos => ViewMode::Gists,
-            ViewMode::Gists => ViewMode::Repos,
+            ViewMode::Gists => ViewMode::Starred,
+            ViewMode::Starred => ViewMode::Repos,
         };
         self.selected = 0;
         self.scroll_offset = 0;
     }
 
     pub fn next_sort_column(&mut self) {
+        if self.view_mode == ViewMode::Gists {
+            self.set_gist_sort(self.gist_sort_column.next(&self.config.gist_columns), self.gist_sort_ascending);
+            return;
+        }
         self.sort_column = self.sort_column.next(&self.config.columns);
         self.config.sort_column = self.sort_column.as_str().to_string();
         self.config.save();
@@ -617,12 +1274,34 @@ impl App {
     }
 
     pub fn prev_sort_column(&mut self) {
+        if self.view_mode == ViewMode::Gists {
+            self.set_gist_sort(self.gist_sort_column.prev(&self.config.gist_columns), self.gist_sort_ascending);
+            return;
+        }
         self.sort_column = self.sort_column.prev(&self.config.columns);
         self.config.sort_column = self.sort_column.as_str().to_string();
         self.config.save();
         self.sort_repos();
     }
 
+    /// Change and persist the gist sort, keeping the cursor on the same gist
+    fn set_gist_sort(&mut self, column: GistSortColumn, ascending: bool) {
+        self.gist_sort_column = column;
+        self.gist_sort_ascending = ascending;
+        self.config.gist_sort_column = column.as_str().to_string();
+        self.config.gist_sort_ascending = ascending;
+        self.config.save();
+        let selected_id = self.selected_id();
+        self.sort_gist_rows();
+        self.reselect(selected_id);
+    }
+
+    fn sort_gist_rows(&mut self) {
+        let column = self.gist_sort_column;
+        let ascending = self.gist_sort_ascending;
+        self.gists.sort_by(|a, b| compare_gists(a, b, column, ascending));
+    }
+
     pub fn toggle_show_archived(&mut self) {
         self.show_archived = !self.show_archived;
         self.config.show_archived = self.show_archived;
@@ -630,155 +1309,342 @@ impl App {
         self.selected = 0;
     }
 
-    pub fn toggle_show_private(&mut self) {
-        self.show_private = !self.show_private;
-        self.config.show_private = self.show_private;
-        self.config.save();
-        self.selected = 0;
+    /// Re-read config.toml and apply the settings it drives
+    pub fn reload_config(&mut self) {
+        match Config::reload() {
+            Ok(config) => {
+                self.apply_config(config);
+                self.set_status_completed("Reloaded config");
+            }
+            Err(e) => self.config_reload_failed(e),
+        }
     }
 
-    fn sort_repos(&mut self) {
-        let username = self.github_username.clone();
-        let sort_col = self.sort_column;
-        let ascending = self.sort_ascending;
-        let local_root = self.local_root.clone();
-
-        self.repos.sort_by(|a, b| {
-            let cmp = match sort_col {
-                SortColumn::Origin => {
-                    let a_owner = a.owner.as_deref().unwrap_or("~");
-                    let b_owner = b.owner.as_deref().unwrap_or("~");
-                    a_owner.to_lowercase().cmp(&b_owner.to_lowercase())
-                }
-                SortColumn::Name => {
-                    a.name.to_lowercase().cmp(&b.name.to_lowercase())
-                }
-                SortColumn::Type => {
-                    // Sort by: src (owned) < clone < fork < local
-                    let a_type = repo_type_sort_order(a, &username);
-                    let b_type = repo_type_sort_order(b, &username);
-                    a_type.cmp(&b_type)
-                }
-                SortColumn::Status => {
-                    // Sort by: dirty < diverged < ahead < behind < synced < no-local
-                    let a_status = status_sort_order(a);
-                    let b_status = status_sort_order(b);
-                    a_status.cmp(&b_status)
-                }
-                SortColumn::LastUpdated => {
-                    // Sort by time, None goes last
-                    match (a.last_commit_time, b.last_commit_time) {
-                        (Some(a_time), Some(b_time)) => a_time.cmp(&b_time),
-                        (Some(_), None) => std::cmp::Ordering::Less,
-                        (None, Some(_)) => std::cmp::Ordering::Greater,
-                        (None, None) => std::cmp::Ordering::Equal,
-                    }
-                }
-                SortColumn::Path => {
-                    let a_path = a.local_path.as_deref().unwrap_or("~");
-                    let b_path = b.local_path.as_deref().unwrap_or("~");
-                    a_path.cmp(b_path)
-                }
-                SortColumn::Dirty => {
-                    // Sort dirty repos first
-                    let a_dirty = a.git_status.as_ref().map(|s| s.is_dirty()).unwrap_or(false);
-                    let b_dirty = b.git_status.as_ref().map(|s| s.is_dirty()).unwrap_or(false);
-                    b_dirty.cmp(&a_dirty) // Reverse so dirty comes first
-                }
-                SortColumn::Private => {
-                    // Sort private repos first
-                    b.is_private.cmp(&a.is_private)
-                }
-                SortColumn::Archived => {
-                    // Sort archived repos first
-                    b.is_archived.cmp(&a.is_archived)
-                }
-                SortColumn::Ghq => {
-                    // Sort by ghq compliance: non-compliant first, then compliant, then N/A
-                    let a_ghq = a.follows_ghq(&local_root);
-                    let b_ghq = b.follows_ghq(&local_root);
-                    match (a_ghq, b_ghq) {
-                        (Some(false), Some(true)) => std::cmp::Ordering::Less,
-                        (Some(true), Some(false)) => std::cmp::Ordering::Greater,
-                        (Some(_), None) => std::cmp::Ordering::Less,
-                        (None, Some(_)) => std::cmp::Ordering::Greater,
-                        _ => std::cmp::Ordering::Equal,
-                    }
+    /// The config file changed on disk; reload unless it only holds our own last save
+    pub fn handle_config_changed(&mut self) {
+        match Config::reload() {
+            Ok(config) => {
+                if toml::to_string(&config).ok() == toml::to_string(&self.config).ok() {
+                    return;
                 }
-            };
-            // Apply ascending/descending
-            let primary = if ascending { cmp } else { cmp.reverse() };
-            // Use repo ID as tie-breaker for stable sorting
-            if primary == std::cmp::Ordering::Equal {
-                a.id.cmp(&b.id)
-            } else {
-                primary
+                self.apply_config(config);
+                self.set_status_completed("Config changed on disk, reloaded");
             }
-        });
+            Err(e) => self.config_reload_failed(e),
+        }
     }
 
-    pub fn visible_repos(&self) -> Vec<&RepoRow> {
-        self.repos
-            .iter()
-            .filter(|r| !self.config.ignored_repos.contains(&r.id))
-            .filter(|r| self.show_archived || !r.is_archived)
-            .filter(|r| self.show_private || !r.is_private)
-            .collect()
+    fn apply_config(&mut self, config: Config) {
+        let selected_id = self.selected_id();
+        self.sort_column = SortColumn::from_string(&config.sort_column);
+        self.sort_ascending = config.sort_ascending;
+        self.gist_sort_column = GistSortColumn::from_string(&config.gist_sort_column);
+        self.gist_sort_ascending = config.gist_sort_ascending;
+        self.show_archived = config.show_archived;
+        self.show_private = config.show_private;
+        self.config = config;
+        if self.selected_column >= self.config.columns.len() {
+            self.selected_column = 0;
+        }
+        if self.selected_gist_column >= self.config.gist_columns.len() {
+            self.selected_gist_column = 0;
+        }
+        self.apply_notes();
+        self.sort_repo_rows();
+        self.sort_gist_rows();
+        self.reselect(selected_id);
     }
 
-    fn visible_list_len(&self) -> usize {
-        match self.view_mode {
-            ViewMode::Repos => self.visible_repos().len(),
-            ViewMode::Gists => self.gists.len(),
-        }
+    fn config_reload_failed(&mut self, error: String) {
+        // Keep the current config so a typo can't be overwritten by the next save
+        self.error_log.push(ErrorLogEntry::new("reload config", error));
+        self.set_status_error("Config not reloaded (E: view errors)");
     }
 
-    pub fn next(&mut self) {
-        let count = self.visible_list_len();
-        if count > 0 {
-            self.selected = (self.selected + 1).min(count - 1);
+    /// Toggle between my personal repos and everything accessible
+    pub fn toggle_only_mine(&mut self) {
+        self.config.only_mine = !self.config.only_mine;
+        self.config.save();
+        self.selected = 0;
+        if self.config.only_mine && self.github_username.is_none() {
+            self.set_status("Only my repos: applies once GitHub user is loaded");
         }
     }
 
-    pub fn previous(&mut self) {
-        self.selected = self.selected.saturating_sub(1);
+    pub fn toggle_show_private(&mut self) {
+        self.show_private = !self.show_private;
+        self.config.show_private = self.show_private;
+        self.config.save();
+        self.selected = 0;
+    }
+
+    /// Toggle showing only repos that have no local clone yet
+    pub fn toggle_remote_only(&mut self) {
+        self.remote_only = !self.remote_only;
+        self.selected = 0;
+        if self.remote_only {
+            let count = self.visible_repos().len();
+            self.set_status_completed(format!("{} repos not cloned", count));
+        }
+    }
+
+    /// Jump to the next (or previous) dirty/ahead/behind repo in the visible list, wrapping around
+    pub fn jump_to_attention(&mut self, forward: bool) {
+        let repos = self.visible_repos();
+        let count = repos.len();
+        if count == 0 {
+            return;
+        }
+        let found = (1..=count)
+            .map(|step| if forward { (self.selected + step) % count } else { (self.selected + count - step) % count })
+            .find(|&idx| StatusFilter::needs_attention(repos[idx].git_status.as_ref()));
+        match found {
+            Some(idx) => self.selected = idx,
+            None => self.set_status_completed("No repos need attention"),
+        }
+    }
+
+    /// Toggle showing only repos that have a note
+    pub fn toggle_notes_only(&mut self) {
+        self.notes_only = !self.notes_only;
+        self.selected = 0;
+        if self.notes_only {
+            let count = self.visible_repos().len();
+            self.set_status_completed(format!("{} repos with notes", count));
+        }
+    }
+
+    /// Cycle the status filter (all → dirty → ahead → behind → diverged)
+    pub fn cycle_status_filter(&mut self) {
+        self.status_filter = self.status_filter.next();
+        self.selected = 0;
+    }
+
+    pub fn cycle_type_filter(&mut self) {
+        self.type_filter = self.type_filter.next();
+        self.selected = 0;
+        if self.type_filter != TypeFilter::All {
+            let count = self.visible_repos().len();
+            self.set_status_completed(format!("{} {}", count, self.type_filter.label()));
+        }
+    }
+
+    fn update_status_summary(&mut self) {
+        let mut summary = StatusSummary::default();
+        for status in self.repos
+            .iter()
+            .filter(|r| !self.config.ignored_repos.contains(&r.id))
+            .filter_map(|r| r.git_status.as_ref())
+        {
+            if StatusFilter::Dirty.matches(Some(status)) {
+                summary.dirty += 1;
+            }
+            if StatusFilter::Ahead.matches(Some(status)) {
+                summary.ahead += 1;
+            }
+            if StatusFilter::Behind.matches(Some(status)) {
+                summary.behind += 1;
+            }
+            if StatusFilter::Diverged.matches(Some(status)) {
+                summary.diverged += 1;
+            }
+        }
+        self.status_summary = summary;
+    }
+
+    /// Re-sort repos, keeping the cursor on the same repo
+    fn sort_repos(&mut self) {
+        let selected_id = self.selected_id();
+        self.sort_repo_rows();
+        self.reselect(selected_id);
+    }
+
+    fn sort_repo_rows(&mut self) {
+        let username = self.github_username.clone();
+        let sort_col = self.sort_column;
+        let ascending = self.sort_ascending;
+        let roots = self.scan_roots.clone();
+
+        let ghq = self.config.ghq_check();
+        self.repos.sort_by(|a, b| compare_repos(a, b, sort_col, ascending, &username, &roots, ghq));
+        self.starred.sort_by(|a, b| compare_repos(a, b, sort_col, ascending, &username, &roots, ghq));
+
+        // Stable sort keeps the column order among pinned and unpinned repos
+        let pinned = &self.config.pinned;
+        self.repos.sort_by_key(|r| !pinned.contains(&r.id));
+        self.starred.sort_by_key(|r| !pinned.contains(&r.id));
+    }
+
+    pub fn visible_repos(&self) -> Vec<&RepoRow> {
+        let rows = match self.view_mode {
+            ViewMode::Starred => &self.starred,
+            _ => &self.repos,
+        };
+        rows.iter()
+            .filter(|r| !self.config.ignored_repos.contains(&r.id))
+            .filter(|r| self.show_archived || !r.is_archived)
+            .filter(|r| self.show_private || !r.is_private)
+            .filter(|r| self.status_filter.matches(r.git_status.as_ref()))
+            .filter(|r| self.type_filter.matches(r, &self.github_username))
+            .filter(|r| !self.remote_only || r.is_remote_only())
+            .filter(|r| !self.notes_only || r.note.is_some())
+            .filter(|r| self.repo_matches_search(r))
+            .filter(|r| self.view_mode == ViewMode::Starred || !self.config.only_mine || self.is_mine(r))
+            .collect()
+    }
+
+    /// Gists matching the search query
+    pub fn visible_gists(&self) -> Vec<&GistRow> {
+        let query = self.search_query.to_lowercase();
+        self.gists
+            .iter()
+            .filter(|g| {
+                query.is_empty()
+                    || g.description.to_lowercase().contains(&query)
+                    || g.file_names.iter().any(|f| f.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    fn repo_matches_search(&self, repo: &RepoRow) -> bool {
+        if self.search_query.is_empty() {
+            return true;
+        }
+        let query = self.search_query.to_lowercase();
+        [repo.owner.as_deref(), Some(repo.name.as_str()), repo.local_path.as_deref()]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(&query))
+    }
+
+    /// Start typing a search filter, continuing any previous query
+    pub fn start_search(&mut self) {
+        self.input_mode = InputMode::Search;
+    }
+
+    /// Keep the query applied and return to normal mode
+    pub fn submit_search(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Drop the query and return to normal mode, keeping the cursor on the same row
+    pub fn clear_search(&mut self) {
+        let selected_id = self.selected_id();
+        self.search_query.clear();
+        self.input_mode = InputMode::Normal;
+        self.reselect(selected_id);
+    }
+
+    /// Whether a repo is owned by the signed-in user; true until the username is known
+    fn is_mine(&self, repo: &RepoRow) -> bool {
+        match (&self.github_username, &repo.owner) {
+            (None, _) => true,
+            (Some(me), Some(owner)) => owner.eq_ignore_ascii_case(me),
+            (Some(_), None) => false,
+        }
+    }
+
+    pub fn visible_list_len(&self) -> usize {
+        match self.view_mode {
+            ViewMode::Repos | ViewMode::Starred => self.visible_repos().len(),
+            ViewMode::Gists => self.visible_gists().len(),
+        }
+    }
+
+    pub fn next(&mut self) {
+        let count = self.visible_list_len();
+        if count > 0 {
+            self.selected = (self.selected + 1).min(count - 1);
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn jump_to_top(&mut self) {
+        self.selected = 0;
+        self.scroll_offset = 0;
+    }
+
+    pub fn jump_to_bottom(&mut self) {
+        self.selected = self.visible_list_len().saturating_sub(1);
+    }
+
+    /// First g arms the sequence; a second within GG_TIMEOUT jumps to the top
+    pub fn press_g(&mut self) {
+        match self.pending_g.take() {
+            Some(at) if at.elapsed() < GG_TIMEOUT => self.jump_to_top(),
+            _ => self.pending_g = Some(Instant::now()),
+        }
+    }
+
+    /// A g left alone past GG_TIMEOUT in the repo views: the local path to open in lazygit
+    pub fn take_lone_g(&mut self) -> Option<String> {
+        match self.pending_g {
+            Some(at) if at.elapsed() >= GG_TIMEOUT => {
+                self.pending_g = None;
+                if self.view_mode.is_repo_list() {
+                    self.get_selected_repo().and_then(|r| r.local_path.clone())
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
     }
 
     /// Toggle mark on currently selected item
     pub fn toggle_mark(&mut self) {
         let id = match self.view_mode {
-            ViewMode::Repos => self.visible_repos().get(self.selected).map(|r| r.id.clone()),
-            ViewMode::Gists => self.gists.get(self.selected).map(|g| g.id.clone()),
+            ViewMode::Repos | ViewMode::Starred => self.visible_repos().get(self.selected).map(|r| r.id.clone()),
+            ViewMode::Gists => self.visible_gists().get(self.selected).map(|g| g.id.clone()),
         };
         if let Some(id) = id {
-            if self.marked.contains(&id) {
-                self.marked.remove(&id);
+            let marked = self.current_marks_mut();
+            if marked.contains(&id) {
+                marked.remove(&id);
             } else {
-                self.marked.insert(id);
+                marked.insert(id);
             }
         }
     }
 
-    /// Check if item is marked
+    /// Marks belonging to the current view mode
+    fn current_marks(&self) -> &HashSet<String> {
+        match self.view_mode {
+            ViewMode::Repos | ViewMode::Starred => &self.marked_repos,
+            ViewMode::Gists => &self.marked_gists,
+        }
+    }
+
+    fn current_marks_mut(&mut self) -> &mut HashSet<String> {
+        match self.view_mode {
+            ViewMode::Repos | ViewMode::Starred => &mut self.marked_repos,
+            ViewMode::Gists => &mut self.marked_gists,
+        }
+    }
+
+    /// Check if item is marked in the current view
     pub fn is_marked(&self, id: &str) -> bool {
-        self.marked.contains(id)
+        self.current_marks().contains(id)
     }
 
-    /// Clear all marks
+    /// Clear all marks in the current view
     pub fn clear_marks(&mut self) {
-        self.marked.clear();
+        self.current_marks_mut().clear();
     }
 
-    /// Get count of marked items
+    /// Get count of marked items in the current view
     pub fn marked_count(&self) -> usize {
-        self.marked.len()
+        self.current_marks().len()
     }
 
     /// Get marked repos that have local paths (for batch operations)
     pub fn marked_local_repos(&self) -> Vec<(String, String)> {
         self.repos
             .iter()
-            .filter(|r| self.marked.contains(&r.id) && r.local_path.is_some())
+            .filter(|r| self.marked_repos.contains(&r.id) && r.local_path.is_some())
             .map(|r| (r.name.clone(), r.local_path.clone().unwrap()))
             .collect()
     }
@@ -804,73 +1670,174 @@ impl App {
         }
     }
 
-    /// Check for completed background tasks (non-blocking)
-    pub fn poll_tasks(&mut self) {
-        while let Ok(result) = self.task_rx.try_recv() {
-            // Handle special messages
-            if result.message.starts_with("__ORGS__:") {
-                let orgs_str = result.message.trim_start_matches("__ORGS__:");
-                if let Some(ref mut form) = self.upload_form {
-                    form.orgs = orgs_str.split(',')
-                        .filter(|s| !s.is_empty())
-                        .map(|s| s.to_string())
-                        .collect();
-                }
-                continue;
+    /// Whether the loop needs periodic ticks (spinner animation, status timeout or a pending g)
+    pub fn needs_tick(&self) -> bool {
+        self.status_message.is_some() || self.pending_g.is_some()
+    }
+
+    /// Record task progress; late updates after the status settled are dropped
+    pub fn handle_progress(&mut self, update: ProgressUpdate) {
+        if self.status_is_loading {
+            self.progress.insert(update.operation, update.percent);
+        }
+    }
+
+    /// Progress callback for a background task; updates are dropped if the UI falls behind
+    fn progress_reporter(&self, operation: &str) -> impl Fn(u8) + Send + Sync + 'static {
+        let tx = self.progress_tx.clone();
+        let operation = operation.to_string();
+        move |percent| {
+            let _ = tx.try_send(ProgressUpdate { operation: operation.clone(), percent });
+        }
+    }
+
+    /// Apply a completed background task result
+    pub fn handle_task_result(&mut self, result: TaskResult) {
+        // Handle special messages
+        if result.message.starts_with("__ORGS__:") {
+            let orgs_str = result.message.trim_start_matches("__ORGS__:");
+            if let Some(ref mut form) = self.upload_form {
+                form.orgs = orgs_str.split(',')
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string())
+                    .collect();
+                // Preselect the last-used org; personal if it's no longer listed
+                form.selected_org = self.config.last_upload_owner.as_ref()
+                    .and_then(|owner| form.orgs.iter().position(|o| o == owner))
+                    .map(|idx| idx + 1)
+                    .unwrap_or(0);
             }
+            return;
+        }
 
-            // Log errors with full stderr
-            if !result.success {
-                if let Some(stderr) = result.stderr {
-                    if !stderr.is_empty() {
-                        self.error_log.push(ErrorLogEntry::new(&result.operation, &stderr));
-                    }
+        // Log errors with full stderr
+        if !result.success {
+            if let Some(stderr) = result.stderr {
+                if !stderr.is_empty() {
+                    self.error_log.push(ErrorLogEntry::new(&result.operation, &stderr));
                 }
             }
+        }
 
-            // Set status as completed (will show tick instead of spinner)
-            self.set_status_completed(result.message.clone());
+        // Set status as completed (will show tick instead of spinner)
+        self.set_status_completed(result.message.clone());
 
-            // Choose refresh type based on whether GitHub cache needs invalidation
-            if result.invalidates_github_cache {
-                self.pending_refresh = true;
-            } else {
-                self.pending_local_refresh = true;
-            }
+        // Choose refresh type based on whether GitHub cache needs invalidation
+        if result.invalidates_github_cache {
+            self.pending_refresh = true;
+        } else {
+            self.pending_local_refresh = true;
         }
     }
 
-    /// Check for completed refresh data (non-blocking)
-    pub fn poll_refresh(&mut self) {
-        while let Ok(data) = self.refresh_rx.try_recv() {
-            // Update app state with refreshed data
-            if data.github_username.is_some() {
-                self.github_username = data.github_username;
-            }
-            self.repos = data.repos;
-            self.gists = data.gists;
+    /// Apply refreshed repo and gist data
+    pub fn handle_refresh_data(&mut self, data: RefreshData) {
+        // Rows may reorder or disappear; remember which one the cursor was on
+        let selected_id = self.selected_id();
 
-            // Store GitHub cache for local-only refreshes
-            if data.github_cache.is_some() {
-                self.github_cache = data.github_cache;
-            }
+        // Update app state with refreshed data
+        if data.github_username.is_some() {
+            self.github_username = data.github_username;
+        }
+        self.repos = data.repos;
+        self.gists = data.gists;
+        self.starred = data.starred;
+        self.apply_notes();
+        self.disk_usage = None; // Clones may have been added, moved or deleted
+        self.disk_usage_generation += 1;
+
+        // Store GitHub cache for local-only refreshes
+        if data.github_cache.is_some() {
+            self.github_cache = data.github_cache;
+        }
 
-            // Re-apply user's sort settings
-            self.sort_repos();
+        // Re-apply user's sort settings and follow the previously selected row
+        self.sort_repo_rows();
+        self.sort_gist_rows();
+        self.update_status_summary();
+        self.reselect(selected_id);
 
-            // Clamp selection to valid range
-            let max = self.visible_list_len().saturating_sub(1);
-            if self.selected > max {
-                self.selected = max;
-            }
+        // Clamp selection to valid range
+        let max = self.visible_list_len().saturating_sub(1);
+        if self.selected > max {
+            self.selected = max;
+        }
 
-            // Show error if auth failed, otherwise show success
-            if let Some(error) = data.error {
-                self.set_status_error(error);
-            } else {
-                self.set_status_completed(format!("Loaded {} repos", self.repos.len()));
-            }
+        // A repo that stays broken reports the same error on every refresh; log and flag it once
+        let new_errors: Vec<ErrorLogEntry> = data.errors
+            .into_iter()
+            .filter(|e| !self.error_log.iter().any(|l| l.operation == e.operation && l.error == e.error))
+            .collect();
+        let status_errors = new_errors.len();
+        self.error_log.extend(new_errors);
+
+        // Show error if auth failed, otherwise show success
+        if let Some(error) = data.error {
+            self.set_status_error(error);
+        } else if status_errors > 0 {
+            self.set_status_error(format!(
+                "Loaded {} repos, {} without status (E: view errors)",
+                self.repos.len(),
+                status_errors
+            ));
+        } else {
+            self.set_status_completed(format!("Loaded {} repos", self.repos.len()));
+        }
+
+        if self.startup_fetch_pending {
+            self.startup_fetch_pending = false;
+            self.fetch_all_local();
+        }
+    }
+
+    /// Fetch every local repo in the background so ahead/behind reflect the remote;
+    /// a local refresh follows when the task result arrives
+    fn fetch_all_local(&mut self) {
+        use futures::stream::{self, StreamExt};
+
+        let repos: Vec<(String, String)> = self.repos.iter()
+            .filter(|r| r.has_git && r.github_url.is_some())
+            .filter_map(|r| r.local_path.clone().map(|p| (r.name.clone(), p)))
+            .collect();
+        if repos.is_empty() {
+            return;
+        }
+
+        let total = repos.len();
+        if self.skip_for_dry_run(format!("fetch {} repos", total)) {
+            return;
         }
+        self.set_status(format!("Fetching {} repos...", total));
+        let tx = self.task_tx.clone();
+        let op = "startup fetch".to_string();
+        let on_progress = self.progress_reporter(&op);
+        tokio::spawn(async move {
+            let mut results = stream::iter(repos)
+                .map(|(name, path)| async move { (name, git::fetch(&path, |_| {}).await) })
+                .buffer_unordered(STARTUP_FETCH_CONCURRENCY);
+
+            let mut done = 0;
+            let mut failures = Vec::new();
+            while let Some((name, result)) = results.next().await {
+                done += 1;
+                on_progress((done * 100 / total) as u8);
+                if !result.success {
+                    failures.push(format!("{}:\n{}", name, result.stderr));
+                }
+            }
+
+            let _ = tx.send(TaskResult {
+                success: failures.is_empty(),
+                message: if failures.is_empty() {
+                    format!("Fetched {} repos", total)
+                } else {
+                    format!("Fetch failed for {} of {} repos (E: view errors)", failures.len(), total)
+                },
+                stderr: if failures.is_empty() { None } else { Some(failures.join("\n")) },
+                operation: op,
+                invalidates_github_cache: false, // Local refresh recomputes statuses
+            }).await;
+        });
     }
 
     /// Show error log popup
@@ -880,9 +1847,17 @@ impl App {
             return;
         }
 
-        let content: Vec<String> = self.error_log.iter().flat_map(|e| {
+        // Include the date once the log spans more than one day
+        let spans_days = match (self.error_log.first(), self.error_log.last()) {
+            (Some(first), Some(last)) => first.timestamp.date_naive() != last.timestamp.date_naive(),
+            _ => false,
+        };
+        let time_format = if spans_days { "%Y-%m-%d %H:%M:%S" } else { "%H:%M:%S" };
+
+        // Newest first so the most recent failure is visible without scrolling
+        let content: Vec<String> = self.error_log.iter().rev().flat_map(|e| {
             vec![
-                format!("[{}] {}", e.timestamp, e.operation),
+                format!("[{}] {}", e.timestamp.format(time_format), e.operation),
                 e.error.clone(),
                 String::new(),
             ]
@@ -891,6 +1866,63 @@ impl App {
         self.popup = Some(Popup::new(PopupType::Errors, content));
     }
 
+    /// Show total disk use of local clones, measuring them in the background on first use
+    pub fn show_disk_usage(&mut self) {
+        if let Some(ref sizes) = self.disk_usage {
+            let total: u64 = sizes.iter().map(|s| s.bytes).sum();
+            let mut content = vec![
+                format!("Total: {} across {} local clones", format_bytes(total), sizes.len()),
+                String::new(),
+                format!("Largest {}:", DISK_USAGE_TOP.min(sizes.len())),
+            ];
+            content.extend(sizes.iter().take(DISK_USAGE_TOP).map(|s| {
+                format!("  {:>9}  {}  {}", format_bytes(s.bytes), s.name, s.path)
+            }));
+            content.push(String::new());
+            content.push("Cached until the next refresh (r)".to_string());
+            self.popup = Some(Popup::new(PopupType::DiskUsage, content));
+            return;
+        }
+        if self.measuring_disk_usage {
+            return;
+        }
+
+        // Subrepos live inside their parent's folder, so only top-level clones are measured
+        let clones: Vec<(String, String)> = self.repos
+            .iter()
+            .filter(|r| !r.is_subrepo)
+            .filter_map(|r| r.local_path.clone().map(|p| (r.name.clone(), p)))
+            .collect();
+        self.measuring_disk_usage = true;
+        self.set_status(format!("Measuring {} local clones...", clones.len()));
+        let tx = self.disk_usage_tx.clone();
+        let generation = self.disk_usage_generation;
+        tokio::task::spawn_blocking(move || {
+            let mut sizes: Vec<CloneSize> = clones
+                .into_iter()
+                .map(|(name, path)| {
+                    let bytes = local::dir_size(Path::new(&path));
+                    CloneSize { name, path, bytes }
+                })
+                .collect();
+            sizes.sort_by_key(|s| std::cmp::Reverse(s.bytes));
+            let _ = tx.blocking_send((generation, sizes));
+        });
+    }
+
+    /// Cache measured clone sizes and open the popup; sizes measured before a refresh are dropped
+    pub fn handle_disk_usage(&mut self, generation: u64, sizes: Vec<CloneSize>) {
+        self.measuring_disk_usage = false;
+        self.clear_status();
+        if generation != self.disk_usage_generation {
+            return;
+        }
+        self.disk_usage = Some(sizes);
+        if self.popup.is_none() && self.input_mode == InputMode::Normal {
+            self.show_disk_usage();
+        }
+    }
+
     /// Get error count for status bar
     pub fn error_count(&self) -> usize {
         self.error_log.len()
@@ -914,6 +1946,7 @@ impl App {
         self.status_time = Some(Instant::now());
         self.status_is_loading = false;
         self.status_is_error = false;
+        self.progress.clear();
     }
 
     /// Set a status message for errors (shows X, persistent, red)
@@ -922,6 +1955,7 @@ impl App {
         self.status_time = Some(Instant::now());
         self.status_is_loading = false;
         self.status_is_error = true;
+        self.progress.clear();
     }
 
     /// Clear status message
@@ -935,6 +1969,10 @@ impl App {
 
     /// Toggle sort direction
     pub fn toggle_sort_direction(&mut self) {
+        if self.view_mode == ViewMode::Gists {
+            self.set_gist_sort(self.gist_sort_column, !self.gist_sort_ascending);
+            return;
+        }
         self.sort_ascending = !self.sort_ascending;
         self.config.sort_ascending = self.sort_ascending;
         self.config.save();
@@ -943,6 +1981,14 @@ impl App {
 
     /// Move selected column left
     pub fn move_column_left(&mut self) {
+        if self.view_mode == ViewMode::Gists {
+            if let Some(col) = self.config.gist_columns.get(self.selected_gist_column).copied() {
+                self.config.move_gist_column_left(col);
+                self.selected_gist_column = self.selected_gist_column.saturating_sub(1);
+                self.config.save();
+            }
+            return;
+        }
         if let Some(col) = self.config.columns.get(self.selected_column).copied() {
             self.config.move_column_left(col);
             if self.selected_column > 0 {
@@ -954,6 +2000,16 @@ impl App {
 
     /// Move selected column right
     pub fn move_column_right(&mut self) {
+        if self.view_mode == ViewMode::Gists {
+            if let Some(col) = self.config.gist_columns.get(self.selected_gist_column).copied() {
+                self.config.move_gist_column_right(col);
+                if self.selected_gist_column + 1 < self.config.gist_columns.len() {
+                    self.selected_gist_column += 1;
+                }
+                self.config.save();
+            }
+            return;
+        }
         if let Some(col) = self.config.columns.get(self.selected_column).copied() {
             self.config.move_column_right(col);
             if self.selected_column < self.config.columns.len() - 1 {
@@ -965,6 +2021,12 @@ impl App {
 
     /// Select next column (for reordering)
     pub fn select_next_column(&mut self) {
+        if self.view_mode == ViewMode::Gists {
+            if !self.config.gist_columns.is_empty() {
+                self.selected_gist_column = (self.selected_gist_column + 1) % self.config.gist_columns.len();
+            }
+            return;
+        }
         if !self.config.columns.is_empty() {
             self.selected_column = (self.selected_column + 1) % self.config.columns.len();
         }
@@ -972,6 +2034,13 @@ impl App {
 
     /// Select previous column (for reordering)
     pub fn select_prev_column(&mut self) {
+        if self.view_mode == ViewMode::Gists {
+            let len = self.config.gist_columns.len();
+            if len > 0 {
+                self.selected_gist_column = (self.selected_gist_column + len - 1) % len;
+            }
+            return;
+        }
         if !self.config.columns.is_empty() {
             if self.selected_column == 0 {
                 self.selected_column = self.config.columns.len() - 1;
@@ -986,40 +2055,144 @@ impl App {
         &self.config.columns
     }
 
+    /// Get visible gist columns
+    pub fn visible_gist_columns(&self) -> &[GistColumn] {
+        &self.config.gist_columns
+    }
+
+    /// Width of a column, using the auto-fit value when one was measured
+    pub fn column_width(&self, column: Column) -> u16 {
+        self.column_widths.get(&column).copied().unwrap_or_else(|| column.width())
+    }
+
+    /// Apply auto-fit widths measured from the current rows
+    pub fn set_column_widths(&mut self, widths: HashMap<Column, u16>) {
+        self.column_widths = widths;
+        self.set_status_completed("Fitted column widths to current rows");
+    }
+
     /// Get selected column index
     pub fn selected_column_index(&self) -> usize {
         self.selected_column
     }
 
+    /// Open the copy menu for the selected repo; entries are "KEY|LABEL|VALUE"
+    pub fn show_copy_menu(&mut self) {
+        let entries = self.get_selected_repo().map(|r| {
+            let mut entries = Vec::new();
+            match r.owner {
+                Some(ref owner) if r.github_url.is_some() => entries.push(format!("n|Owner/name|{}/{}", owner, r.name)),
+                _ => entries.push(format!("n|Name|{}", r.name)),
+            }
+            if let Some(ref url) = r.github_url {
+                // Unmatched local repos carry their remote URL, which may be another host
+                if normalize_github_url(url).starts_with("https://github.com/") {
+                    entries.push(format!("u|GitHub URL|{}", url));
+                }
+                if url.starts_with("https://") {
+                    entries.push(format!("h|HTTPS clone URL|{}.git", url.trim_end_matches(".git")));
+                }
+                entries.push(format!("c|Clone command|git clone {}", url));
+            }
+            // Rows without GitHub data reuse the remote URL here, so only list a distinct SSH form
+            if let Some(ssh_url) = r.ssh_url.as_ref().filter(|ssh| r.github_url.as_ref() != Some(*ssh)) {
+                entries.push(format!("s|SSH clone URL|{}", ssh_url));
+            }
+            if let Some(ref path) = r.local_path {
+                entries.push(format!("p|Local path|{}", path));
+            }
+            entries
+        });
+        if let Some(entries) = entries {
+            if !entries.is_empty() {
+                self.popup = Some(Popup::new(PopupType::Copy, entries));
+            }
+        }
+    }
+
+    /// Copy the copy-menu entry bound to `key` and close the menu
+    pub fn copy_menu_entry(&mut self, key: char) {
+        let value = self.popup.as_ref().and_then(|popup| {
+            popup.content.iter().find_map(|line| {
+                let mut parts = line.splitn(3, '|');
+                match (parts.next(), parts.next(), parts.next()) {
+                    (Some(k), Some(label), Some(value)) if k.starts_with(key) => {
+                        Some((label.to_string(), value.to_string()))
+                    }
+                    _ => None,
+                }
+            })
+        });
+        if let Some((label, value)) = value {
+            self.close_popup();
+            match platform::copy_to_clipboard(&value) {
+                Ok(()) => self.set_status_completed(format!("Copied {}", label.to_lowercase())),
+                Err(_) => self.set_status(platform::CLIPBOARD_INSTALL_HINT),
+            }
+        }
+    }
+
+    /// Open the selected repo or gist on GitHub in the browser
+    pub fn open_in_browser(&mut self) {
+        let url = match self.view_mode {
+            ViewMode::Repos | ViewMode::Starred => self.get_selected_repo().and_then(|r| r.github_url.clone()),
+            ViewMode::Gists => self.get_selected_gist().map(|g| g.html_url.clone()),
+        };
+        if let Some(url) = url {
+            self.open_url(&url);
+        }
+    }
+
+    /// Open a subpage of the selected repo on GitHub, e.g. "issues" or "pulls"
+    pub fn open_repo_page(&mut self, page: &str) {
+        if let Some(url) = self.get_selected_repo().and_then(|r| r.github_url.clone()) {
+            self.open_url(&format!("{}/{}", url.trim_end_matches('/'), page));
+        }
+    }
+
+    fn open_url(&mut self, url: &str) {
+        match platform::open(url) {
+            Ok(()) => self.set_status_completed("Opened in browser"),
+            Err(e) => {
+                self.error_log.push(ErrorLogEntry::new(format!("open {}", url), e.to_string()));
+                self.set_status_error("Failed to open browser (E: view errors)");
+            }
+        }
+    }
+
+    /// Open the selected repo's local folder in the system file manager
+    pub fn open_in_file_manager(&mut self) {
+        if let Some(path) = self.get_selected_repo().and_then(|r| r.local_path.clone()) {
+            if !Path::new(&path).is_dir() {
+                self.set_status_error(format!("{} no longer exists (r: refresh)", path));
+                return;
+            }
+            match platform::open(&path) {
+                Ok(()) => self.set_status_completed("Opened in file manager"),
+                Err(e) => {
+                    self.error_log.push(ErrorLogEntry::new(format!("open {}", path), e.to_string()));
+                    self.set_status_error("Failed to open file manager (E: view errors)");
+                }
+            }
+        }
+    }
+
+    /// Copy the visible table, already rendered as TSV by the UI
+    pub fn copy_table(&mut self, tsv: String) {
+        let rows = self.visible_list_len();
+        match platform::copy_to_clipboard(&tsv) {
+            Ok(()) => self.set_status_completed(format!("Copied {} rows as TSV", rows)),
+            Err(_) => self.set_status(platform::CLIPBOARD_INSTALL_HINT),
+        }
+    }
+
     /// Copy popup content to clipboard
     pub fn copy_popup_to_clipboard(&mut self) {
         if let Some(ref popup) = self.popup {
             let content = popup.content.join("\n");
-            // Try wl-copy first (Wayland), then xclip (X11)
-            let result = std::process::Command::new("wl-copy")
-                .arg(&content)
-                .status()
-                .or_else(|_| {
-                    std::process::Command::new("xclip")
-                        .args(["-selection", "clipboard"])
-                        .stdin(std::process::Stdio::piped())
-                        .spawn()
-                        .and_then(|mut child| {
-                            use std::io::Write;
-                            if let Some(stdin) = child.stdin.as_mut() {
-                                stdin.write_all(content.as_bytes())?;
-                            }
-                            child.wait()
-                        })
-                });
-
-            match result {
-                Ok(status) if status.success() => {
-                    self.set_status("Copied to clipboard");
-                }
-                _ => {
-                    self.set_status("Failed to copy (install wl-copy or xclip)");
-                }
+            match platform::copy_to_clipboard(&content) {
+                Ok(()) => self.set_status("Copied to clipboard"),
+                Err(_) => self.set_status(platform::CLIPBOARD_INSTALL_HINT),
             }
         }
     }
@@ -1032,13 +2205,28 @@ impl App {
         }
     }
 
+    /// Scroll the table just enough to keep the selected row on screen
+    pub fn keep_selected_visible(&mut self, visible_rows: usize) {
+        if visible_rows == 0 {
+            return;
+        }
+        if self.selected < self.scroll_offset {
+            self.scroll_offset = self.selected;
+        } else if self.selected >= self.scroll_offset + visible_rows {
+            self.scroll_offset = self.selected + 1 - visible_rows;
+        }
+        // Don't leave blank rows at the bottom once the list shrinks
+        let max_offset = self.visible_list_len().saturating_sub(visible_rows);
+        self.scroll_offset = self.scroll_offset.min(max_offset);
+    }
+
     /// Handle mouse click at position, returning true if it hit the table
     pub fn handle_mouse_click(&mut self, row: u16, _col: u16) -> bool {
         if let Some((table_y, table_height)) = self.table_area {
             // Account for border (1) and header (1) = 2 rows offset
             let header_offset = 2u16;
             if row >= table_y + header_offset && row < table_y + table_height {
-                let clicked_row = (row - table_y - header_offset) as usize;
+                let clicked_row = (row - table_y - header_offset) as usize + self.scroll_offset;
                 self.select_row(clicked_row);
                 return true;
             }
@@ -1058,32 +2246,102 @@ impl App {
         }
     }
 
-    pub fn get_selected_repo(&self) -> Option<&RepoRow> {
-        if self.view_mode == ViewMode::Repos {
-            self.visible_repos().get(self.selected).copied()
-        } else {
-            None
+    /// Start typing a filter in the popup (Help only)
+    pub fn start_popup_filter(&mut self) {
+        if let Some(ref mut popup) = self.popup {
+            popup.filter = Some(String::new());
         }
     }
 
-    pub fn get_selected_gist(&self) -> Option<&GistRow> {
-        if self.view_mode == ViewMode::Gists {
-            self.gists.get(self.selected)
-        } else {
-            None
-        }
+    pub fn push_popup_filter(&mut self, c: char) {
+        if let Some(ref mut popup) = self.popup {
+            if let Some(ref mut filter) = popup.filter {
+                filter.push(c);
+                popup.scroll = 0;
+            }
+        }
+    }
+
+    pub fn pop_popup_filter(&mut self) {
+        if let Some(ref mut popup) = self.popup {
+            if let Some(ref mut filter) = popup.filter {
+                filter.pop();
+                popup.scroll = 0;
+            }
+        }
+    }
+
+    /// Drop the filter and show the full list again
+    pub fn clear_popup_filter(&mut self) {
+        if let Some(ref mut popup) = self.popup {
+            popup.filter = None;
+            popup.scroll = 0;
+        }
+    }
+
+    /// ID of the selected repo or gist, used to keep the cursor on it when rows reorder
+    fn selected_id(&self) -> Option<String> {
+        match self.view_mode {
+            ViewMode::Repos | ViewMode::Starred => self.get_selected_repo().map(|r| r.id.clone()),
+            ViewMode::Gists => self.get_selected_gist().map(|g| g.id.clone()),
+        }
+    }
+
+    /// Move the cursor back onto the row with this ID, if it is still visible
+    fn reselect(&mut self, id: Option<String>) {
+        if let Some(id) = id {
+            let index = match self.view_mode {
+                ViewMode::Repos | ViewMode::Starred => self.visible_repos().iter().position(|r| r.id == id),
+                ViewMode::Gists => self.visible_gists().iter().position(|g| g.id == id),
+            };
+            if let Some(index) = index {
+                self.selected = index;
+            }
+        }
+    }
+
+    pub fn get_selected_repo(&self) -> Option<&RepoRow> {
+        if self.view_mode.is_repo_list() {
+            self.visible_repos().get(self.selected).copied()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_selected_gist(&self) -> Option<&GistRow> {
+        if self.view_mode == ViewMode::Gists {
+            self.visible_gists().get(self.selected).copied()
+        } else {
+            None
+        }
     }
 
     pub fn toggle_help(&mut self) {
         if self.popup.is_some() {
+            self.remember_help_scroll();
             self.popup = None;
         } else {
-            self.popup = Some(Popup::new(PopupType::Help, get_help_content(&self.view_mode)));
+            let mut popup = Popup::new(PopupType::Help, get_help_content(&self.view_mode));
+            popup.scroll = self.help_scroll;
+            self.popup = Some(popup);
+        }
+    }
+
+    /// Keep the unfiltered Help scroll position for the next time it opens
+    fn remember_help_scroll(&mut self) {
+        if let Some(ref popup) = self.popup {
+            if popup.popup_type == PopupType::Help && popup.filter.is_none() {
+                self.help_scroll = popup.scroll;
+            }
         }
     }
 
     pub fn close_popup(&mut self) {
+        self.remember_help_scroll();
         self.popup = None;
+        self.pending_confirm = None;
+        self.confirm_dialog = None;
+        self.pending_delete_id = None;
         self.input_mode = InputMode::Normal;
         self.input_buffer.clear();
         self.confirm_buffer.clear();
@@ -1092,7 +2350,7 @@ impl App {
     // Show details popup for selected item
     pub fn show_details(&mut self) {
         match self.view_mode {
-            ViewMode::Repos => {
+            ViewMode::Repos | ViewMode::Starred => {
                 if let Some(repo) = self.get_selected_repo() {
                     let mut content = vec![
                         format!("Name: {}", repo.name),
@@ -1116,11 +2374,28 @@ impl App {
                         content.push(format!("Subrepo of: {}", repo.parent_repo.as_deref().unwrap_or("unknown")));
                     }
                     content.push(format!("Private: {}", if repo.is_private { "yes" } else { "no" }));
+                    if repo.is_archived {
+                        content.push("Archived: yes (read-only, a to unarchive)".to_string());
+                    }
+                    if repo.is_empty {
+                        content.push("Empty: yes (no commits on GitHub yet)".to_string());
+                    }
+                    if repo.is_shallow {
+                        content.push("Shallow: yes (truncated history, U to unshallow)".to_string());
+                    }
+                    if repo.is_watched {
+                        content.push("Watching: yes (W to unwatch)".to_string());
+                    }
 
                     if let Some(ref status) = repo.git_status {
                         content.push("".to_string());
                         content.push("Git Status:".to_string());
-                        content.push(format!("  Branch: {}", status.branch));
+                        match repo.default_branch_name() {
+                            Some(default) if repo.is_off_default_branch() => {
+                                content.push(format!("  Branch: {} (default: {})", status.branch, default));
+                            }
+                            _ => content.push(format!("  Branch: {}", status.branch)),
+                        }
                         if status.has_remote {
                             content.push(format!("  Ahead: {}, Behind: {}", status.ahead, status.behind));
                         } else {
@@ -1133,6 +2408,7 @@ impl App {
 
                     self.popup = Some(Popup::new(PopupType::Details, content));
                 }
+                self.load_authorship();
             }
             ViewMode::Gists => {
                 if let Some(gist) = self.get_selected_gist() {
@@ -1160,6 +2436,30 @@ impl App {
         }
     }
 
+    /// Compare recent authors with the configured git user off the input loop,
+    /// then append the result to the details popup if it still shows that repo
+    fn load_authorship(&mut self) {
+        let info = self.get_selected_repo()
+            .filter(|r| r.has_git)
+            .and_then(|r| r.local_path.clone().map(|p| (r.id.clone(), p)));
+        if let Some((id, path)) = info {
+            self.load_in_background(async move {
+                let identity = git::user_identity(&path).await;
+                let authors = git::recent_authors(&path, AUTHORSHIP_COMMIT_COUNT).await;
+                let authorship = format_authorship(identity.as_ref(), &authors);
+                move |app: &mut App| {
+                    if authorship.is_empty() || app.selected_id().as_deref() != Some(id.as_str()) {
+                        return;
+                    }
+                    if let Some(popup) = app.popup.as_mut().filter(|p| p.popup_type == PopupType::Details) {
+                        popup.content.push(String::new());
+                        popup.content.extend(authorship);
+                    }
+                }
+            });
+        }
+    }
+
     // Toggle ignore for selected repo
     pub fn toggle_ignore(&mut self) {
         if let Some(repo) = self.get_selected_repo() {
@@ -1179,6 +2479,77 @@ impl App {
         }
     }
 
+    /// Cycle the Updated column: coarse age → fine age → commit hash
+    pub fn cycle_updated_display(&mut self) {
+        if self.show_commit_hash {
+            self.show_commit_hash = false;
+            self.config.updated.fine = false;
+            self.set_status_completed("Updated: coarse ages");
+        } else if self.config.updated.fine {
+            self.show_commit_hash = true;
+            self.set_status_completed("Updated: last commit hash");
+        } else {
+            self.config.updated.fine = true;
+            self.set_status_completed("Updated: fine ages (days/hours)");
+        }
+        self.config.save();
+    }
+
+    pub fn toggle_pin(&mut self) {
+        if let Some(repo) = self.get_selected_repo() {
+            let id = repo.id.clone();
+            let name = repo.name.clone();
+            if self.config.pinned.remove(&id) {
+                self.set_status_completed(format!("Unpinned {}", name));
+            } else {
+                self.config.pinned.insert(id);
+                self.set_status_completed(format!("Pinned {}", name));
+            }
+            self.config.save();
+            self.sort_repos();
+        }
+    }
+
+    /// Copy notes from the config onto the current rows
+    fn apply_notes(&mut self) {
+        let notes = &self.config.notes;
+        for repo in self.repos.iter_mut().chain(self.starred.iter_mut()) {
+            repo.note = notes.get(&repo.id).cloned();
+        }
+    }
+
+    /// Open the note input for the selected repo, prefilled with its current note
+    pub fn start_note(&mut self) {
+        if let Some(repo) = self.get_selected_repo() {
+            let id = repo.id.clone();
+            self.input_buffer = repo.note.clone().unwrap_or_default();
+            self.note_target = Some(id);
+            self.input_mode = InputMode::Note;
+        }
+    }
+
+    /// Save the note input; an empty note removes it
+    pub fn submit_note(&mut self) {
+        if let Some(id) = self.note_target.take() {
+            let note = self.input_buffer.trim().to_string();
+            if note.is_empty() {
+                self.config.notes.remove(&id);
+            } else {
+                self.config.notes.insert(id, note);
+            }
+            self.config.save();
+            self.apply_notes();
+            self.sort_repos();
+        }
+        self.cancel_note();
+    }
+
+    pub fn cancel_note(&mut self) {
+        self.note_target = None;
+        self.input_buffer.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
     // Show ignored repos popup
     pub fn show_ignored_popup(&mut self) {
         if self.config.ignored_repos.is_empty() {
@@ -1213,17 +2584,22 @@ impl App {
     pub fn pull_selected(&mut self) {
         let info = self.get_selected_repo().map(|r| (r.name.clone(), r.local_path.clone()));
         if let Some((name, Some(path))) = info {
+            if self.skip_for_dry_run(format!("pull {}", name)) {
+                return;
+            }
             self.set_status(format!("Pulling {}...", name));
             let tx = self.task_tx.clone();
             let op = format!("pull {}", name);
+            let on_progress = self.progress_reporter(&op);
             tokio::spawn(async move {
-                let result = git::pull(&path).await;
+                let (result, pulled) = git::pull_counted(&path, on_progress).await;
                 let _ = tx.send(TaskResult {
                     success: result.success,
-                    message: if result.success {
-                        format!("Pulled {}", name)
-                    } else {
-                        "Pull failed (E: view errors)".to_string()
+                    message: match (result.success, pulled) {
+                        (true, Some(0)) => format!("{} already up to date", name),
+                        (true, Some(n)) => format!("Pulled {} (+{})", name, n),
+                        (true, None) => format!("Pulled {}", name),
+                        (false, _) => "Pull failed (E: view errors)".to_string(),
                     },
                     stderr: if result.success { None } else { Some(result.stderr) },
                     operation: op,
@@ -1233,9 +2609,35 @@ impl App {
         }
     }
 
+    /// In dry-run mode, report what an action would do instead of running it
+    fn skip_for_dry_run(&mut self, action: String) -> bool {
+        if self.dry_run {
+            self.set_status_completed(format!("(dry-run) would {}", action));
+        }
+        self.dry_run
+    }
+
+    /// Archived repos are read-only on GitHub; report why a push-type action was refused
+    fn refuse_if_archived(&mut self, action: &str) -> bool {
+        let archived = self.get_selected_repo()
+            .filter(|r| r.is_archived)
+            .map(|r| r.name.clone());
+        if let Some(name) = archived {
+            self.set_status_error(format!("Can't {} {}: archived repos are read-only (a: unarchive)", action, name));
+            return true;
+        }
+        false
+    }
+
     pub fn push_selected(&mut self) {
+        if self.refuse_if_archived("push") {
+            return;
+        }
         let info = self.get_selected_repo().map(|r| (r.name.clone(), r.local_path.clone()));
         if let Some((name, Some(path))) = info {
+            if self.skip_for_dry_run(format!("push {}", name)) {
+                return;
+            }
             self.set_status(format!("Pushing {}...", name));
             let tx = self.task_tx.clone();
             let op = format!("push {}", name);
@@ -1257,14 +2659,21 @@ impl App {
     }
 
     pub fn sync_selected(&mut self) {
+        if self.refuse_if_archived("sync") {
+            return;
+        }
         let info = self.get_selected_repo().map(|r| (r.name.clone(), r.local_path.clone()));
         if let Some((name, Some(path))) = info {
+            if self.skip_for_dry_run(format!("sync {}", name)) {
+                return;
+            }
             self.set_status(format!("Syncing {}...", name));
             let tx = self.task_tx.clone();
             let op = format!("sync {}", name);
+            let on_progress = self.progress_reporter(&op);
             tokio::spawn(async move {
-                let fetch_res = git::fetch(&path).await;
-                let pull_res = git::pull(&path).await;
+                let fetch_res = git::fetch(&path, &on_progress).await;
+                let pull_res = git::pull(&path, &on_progress).await;
                 let push_res = git::push(&path).await;
                 let success = fetch_res.success && pull_res.success && push_res.success;
                 let stderr = if !success {
@@ -1293,11 +2702,33 @@ impl App {
 
     /// Quicksync: fetch, ff-rebase, add all, commit with fixup, push
     pub fn quicksync_selected(&mut self) {
-        // If marked repos exist, quicksync all of them
+        // If marked repos exist, quicksync all of them except archived ones
         let marked = self.marked_local_repos();
         if !marked.is_empty() {
+            // Archived repos are read-only; shallow ones need an explicit unshallow before rebasing
+            let skip: HashSet<String> = self.repos
+                .iter()
+                .filter(|r| (r.is_archived || r.is_shallow) && self.marked_repos.contains(&r.id))
+                .filter_map(|r| r.local_path.clone())
+                .collect();
+            let skipped = skip.len();
+            let marked: Vec<(String, String)> = marked
+                .into_iter()
+                .filter(|(_, path)| !skip.contains(path))
+                .collect();
             let count = marked.len();
-            self.set_status(format!("Quicksyncing {} repos...", count));
+            if count == 0 {
+                self.set_status_error("All marked repos are archived or shallow; nothing to quicksync");
+                return;
+            }
+            if self.skip_for_dry_run(format!("quicksync {} repos", count)) {
+                return;
+            }
+            if skipped > 0 {
+                self.set_status(format!("Quicksyncing {} repos (skipping {} archived/shallow)...", count, skipped));
+            } else {
+                self.set_status(format!("Quicksyncing {} repos...", count));
+            }
             let tx = self.task_tx.clone();
             tokio::spawn(async move {
                 let mut success_count = 0;
@@ -1328,8 +2759,32 @@ impl App {
             self.clear_marks();
         } else {
             // Single repo quicksync
+            if self.refuse_if_archived("quicksync") {
+                return;
+            }
+
+            // Rebasing on truncated history can rewrite more than intended; unshallow first
+            let shallow = self.get_selected_repo()
+                .filter(|r| r.is_shallow)
+                .map(|r| (r.id.clone(), r.name.clone()));
+            if let Some((id, name)) = shallow {
+                let content = vec![
+                    format!("{} is a shallow clone.", name),
+                    String::new(),
+                    "Quicksync rebases onto upstream, which is unsafe".to_string(),
+                    "on truncated history.".to_string(),
+                    String::new(),
+                    "Press y or Enter to unshallow, then quicksync; Esc to cancel".to_string(),
+                ];
+                self.open_confirm(ConfirmAction::UnshallowThenQuicksync { id }, content);
+                self.set_status_error(format!("{} is shallow: confirm to unshallow before quicksync", name));
+                return;
+            }
             let info = self.get_selected_repo().map(|r| (r.name.clone(), r.local_path.clone()));
             if let Some((name, Some(path))) = info {
+                if self.skip_for_dry_run(format!("quicksync {}", name)) {
+                    return;
+                }
                 self.set_status(format!("Quicksyncing {}...", name));
                 let tx = self.task_tx.clone();
                 let op = format!("quicksync {}", name);
@@ -1351,6 +2806,185 @@ impl App {
         }
     }
 
+    /// Open the branch name input for the repo the branches popup was opened for
+    pub fn start_new_branch(&mut self) {
+        if let Some(target) = self.branches_repo.clone() {
+            self.input_buffer.clear();
+            self.branch_target = Some(target);
+            self.input_mode = InputMode::NewBranch;
+        }
+    }
+
+    /// Create and switch to the typed branch; invalid names keep the input open
+    pub fn submit_new_branch(&mut self) {
+        let branch = self.input_buffer.trim().to_string();
+        if let Some(error) = git::branch_name_error(&branch) {
+            self.set_status_error(format!("Invalid branch name: {}", error));
+            return;
+        }
+        if let Some((name, path)) = self.branch_target.take() {
+            self.cancel_new_branch();
+            if self.skip_for_dry_run(format!("create branch {} in {}", branch, name)) {
+                return;
+            }
+            self.set_status(format!("Creating branch {} in {}...", branch, name));
+            let tx = self.task_tx.clone();
+            let op = format!("create branch {} in {}", branch, name);
+            tokio::spawn(async move {
+                let result = git::create_branch(&path, &branch).await;
+                let _ = tx.send(TaskResult {
+                    success: result.success,
+                    message: if result.success {
+                        format!("Switched {} to new branch {}", name, branch)
+                    } else {
+                        "Create branch failed (E: view errors)".to_string()
+                    },
+                    stderr: if result.success { None } else { Some(result.stderr) },
+                    operation: op,
+                    invalidates_github_cache: false, // Local git operation
+                }).await;
+            });
+        }
+    }
+
+    pub fn cancel_new_branch(&mut self) {
+        self.branch_target = None;
+        self.input_buffer.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Fetch full history for a shallow clone
+    pub fn unshallow_selected(&mut self) {
+        let info = self.get_selected_repo()
+            .filter(|r| r.is_shallow)
+            .and_then(|r| r.local_path.clone().map(|p| (r.name.clone(), p)));
+        if let Some((name, path)) = info {
+            if self.skip_for_dry_run(format!("unshallow {}", name)) {
+                return;
+            }
+            self.set_status(format!("Unshallowing {}...", name));
+            let tx = self.task_tx.clone();
+            let op = format!("unshallow {}", name);
+            tokio::spawn(async move {
+                let result = git::unshallow(&path).await;
+                let _ = tx.send(TaskResult {
+                    success: result.success,
+                    message: if result.success {
+                        format!("Fetched full history for {}", name)
+                    } else {
+                        "Unshallow failed (E: view errors)".to_string()
+                    },
+                    stderr: if result.success { None } else { Some(result.stderr) },
+                    operation: op,
+                    invalidates_github_cache: false, // Local git operation
+                }).await;
+            });
+        }
+    }
+
+    /// Watch or unwatch the selected repo's GitHub notifications
+    pub fn toggle_watch_selected(&mut self) {
+        let info = self.get_selected_repo().and_then(|r| {
+            r.owner.clone().map(|o| (format!("{}/{}", o, r.name), r.is_watched))
+        });
+        if let Some((name_with_owner, is_watched)) = info {
+            if self.skip_for_dry_run(format!("{} {}", if is_watched { "unwatch" } else { "watch" }, name_with_owner)) {
+                return;
+            }
+            let verb = if is_watched { "Unwatching" } else { "Watching" };
+            self.set_status(format!("{} {}...", verb, name_with_owner));
+            let tx = self.task_tx.clone();
+            let op = format!("watch {}", name_with_owner);
+            tokio::spawn(async move {
+                let result = github::set_subscription(&name_with_owner, !is_watched).await;
+                let _ = tx.send(TaskResult {
+                    success: result.success,
+                    message: if result.success {
+                        let done = if is_watched { "Unwatched" } else { "Watching" };
+                        format!("{} {}", done, name_with_owner)
+                    } else {
+                        "Watch change failed (E: view errors)".to_string()
+                    },
+                    stderr: if result.success { None } else { Some(result.stderr) },
+                    operation: op,
+                    invalidates_github_cache: true, // Subscription state changed
+                }).await;
+            });
+        } else {
+            self.set_status_error("Not a GitHub repo");
+        }
+    }
+
+    /// Star or unstar the selected repo
+    pub fn toggle_star_selected(&mut self) {
+        let info = self.get_selected_repo().and_then(|r| {
+            r.owner.clone().map(|o| (format!("{}/{}", o, r.name), r.is_starred))
+        });
+        if let Some((name_with_owner, is_starred)) = info {
+            if self.skip_for_dry_run(format!("{} {}", if is_starred { "unstar" } else { "star" }, name_with_owner)) {
+                return;
+            }
+            let verb = if is_starred { "Unstarring" } else { "Starring" };
+            self.set_status(format!("{} {}...", verb, name_with_owner));
+            let tx = self.task_tx.clone();
+            let op = format!("star {}", name_with_owner);
+            tokio::spawn(async move {
+                let result = github::set_starred(&name_with_owner, !is_starred).await;
+                let _ = tx.send(TaskResult {
+                    success: result.success,
+                    message: if result.success {
+                        let done = if is_starred { "Unstarred" } else { "Starred" };
+                        format!("{} {}", done, name_with_owner)
+                    } else {
+                        "Star change failed (E: view errors)".to_string()
+                    },
+                    stderr: if result.success { None } else { Some(result.stderr) },
+                    operation: op,
+                    invalidates_github_cache: true, // Star state changed
+                }).await;
+            });
+        } else {
+            self.set_status_error("Not a GitHub repo");
+        }
+    }
+
+    /// Unshallow a repo, then quicksync it once full history is available
+    fn unshallow_then_quicksync(&mut self, id: &str) {
+        let info = self.repos.iter()
+            .chain(self.starred.iter())
+            .find(|r| r.id == id)
+            .and_then(|r| r.local_path.clone().map(|p| (r.name.clone(), p)));
+        if let Some((name, path)) = info {
+            if self.skip_for_dry_run(format!("unshallow and quicksync {}", name)) {
+                return;
+            }
+            self.set_status(format!("Unshallowing {}, then quicksyncing...", name));
+            let tx = self.task_tx.clone();
+            let op = format!("quicksync {}", name);
+            tokio::spawn(async move {
+                let unshallow = git::unshallow(&path).await;
+                let result = if unshallow.success {
+                    git::quicksync(&path).await
+                } else {
+                    unshallow
+                };
+                let _ = tx.send(TaskResult {
+                    success: result.success,
+                    message: if result.success {
+                        format!("Unshallowed and quicksynced {}", name)
+                    } else {
+                        "Unshallow/quicksync failed (E: view errors)".to_string()
+                    },
+                    stderr: if result.success { None } else { Some(result.stderr) },
+                    operation: op,
+                    invalidates_github_cache: false,
+                }).await;
+            });
+        } else {
+            self.set_status_error("Repo no longer has a local clone (r: refresh)");
+        }
+    }
+
     pub fn clone_selected(&mut self) {
         let info = self.get_selected_repo().and_then(|r| {
             if r.is_remote_only() {
@@ -1361,18 +2995,29 @@ impl App {
             }
         });
         if let Some((name, url)) = info {
-            let clone_path = get_ghq_path(&self.local_root, &url);
+            let clone_path = get_ghq_path(self.primary_root(), &url, self.config.ghq_layout);
+            let retries = self.config.clone_retries;
+            let clone_args = self.config.clone_args.clone();
+            if let Some(e) = git::clone_args_error(&clone_args) {
+                self.set_status_error(format!("Invalid clone_args in config: {}", e));
+                return;
+            }
+            if self.skip_for_dry_run(format!("clone {} to {}", name, clone_path)) {
+                return;
+            }
             self.set_status(format!("Cloning {}...", name));
             let tx = self.task_tx.clone();
             let op = format!("clone {}", name);
+            let on_progress = self.progress_reporter(&op);
             tokio::spawn(async move {
-                let result = git::clone(&url, &clone_path).await;
+                let (result, attempts) = git::clone_with_retry(&url, &clone_path, &clone_args, retries, on_progress).await;
                 let _ = tx.send(TaskResult {
                     success: result.success,
-                    message: if result.success {
-                        format!("Cloned {}", name)
-                    } else {
-                        "Clone failed (E: view errors)".to_string()
+                    message: match (result.success, attempts) {
+                        (true, 1) => format!("Cloned {}", name),
+                        (true, n) => format!("Cloned {} (after {} attempts)", name, n),
+                        (false, 1) => "Clone failed (E: view errors)".to_string(),
+                        (false, n) => format!("Clone failed after {} attempts (E: view errors)", n),
                     },
                     stderr: if result.success { None } else { Some(result.stderr) },
                     operation: op,
@@ -1385,6 +3030,9 @@ impl App {
     pub fn init_repo(&mut self) {
         let info = self.get_selected_repo().map(|r| (r.name.clone(), r.local_path.clone(), r.has_git));
         if let Some((name, Some(path), false)) = info {
+            if self.skip_for_dry_run(format!("git init {}", path)) {
+                return;
+            }
             self.set_status(format!("Initializing git repo in {}...", name));
             let tx = self.task_tx.clone();
             let op = format!("init {}", name);
@@ -1412,95 +3060,190 @@ impl App {
             .map(|r| r.has_local())
             .unwrap_or(false);
         if has_marked || has_local {
-            self.input_mode = InputMode::ConfirmDelete;
-            self.pending_delete = Some(DeleteType::LocalRepo);
-            self.confirm_buffer.clear();
+            self.pending_delete_id = self.get_selected_repo().map(|r| r.id.clone());
+            // Batch deletes always show their summary; only a single clone skips the prompt
+            if self.config.confirm_level == ConfirmLevel::None && !has_marked {
+                self.run_local_delete();
+                return;
+            }
+
+            // A folder without git has no history to lose, so y/Enter is enough
+            let nogit = self.get_selected_repo()
+                .filter(|r| !has_marked && !r.has_git)
+                .and_then(|r| r.local_path.clone().map(|p| (r.id.clone(), p)));
+            if let Some((id, path)) = nogit {
+                let content = vec![
+                    format!("Delete folder {}?", path),
+                    String::new(),
+                    "It is not a git repo, so there is no history at stake.".to_string(),
+                    String::new(),
+                    "Press y or Enter to delete, Esc to cancel".to_string(),
+                ];
+                self.open_confirm(ConfirmAction::DeleteFolder { id }, content);
+                return;
+            }
+
+            // Batch deletes require the full word; single deletes take a y keypress
+            let marked = self.marked_local_repos();
+            let dialog = if marked.is_empty() {
+                let target = self.get_selected_repo().map(|r| r.name.clone()).unwrap_or_default();
+                let path = self.get_selected_repo().and_then(|r| r.local_path.clone()).unwrap_or_default();
+                ConfirmDialog::yes_no(
+                    " Confirm Delete Local ",
+                    vec![format!("Delete {} locally? [y/n]", target), String::new(), format!("  {}", path)],
+                )
+            } else {
+                let mut message = vec![format!("Delete {} local clones:", marked.len()), String::new()];
+                message.extend(marked.iter().map(|(name, path)| format!("  {}  {}", name, path)));
+                ConfirmDialog::typed(" Confirm Batch Delete ", message, Some("yes".to_string()))
+            };
+            self.open_typed_confirm(DeleteType::LocalRepo, dialog);
         }
     }
 
+    /// Switch to typed confirmation for a delete
+    fn open_typed_confirm(&mut self, delete: DeleteType, dialog: ConfirmDialog) {
+        self.input_mode = InputMode::ConfirmDelete;
+        self.pending_delete = Some(delete);
+        self.confirm_dialog = Some(dialog);
+        self.confirm_buffer.clear();
+    }
+
+    /// Whether the open delete dialog was accepted; y/n dialogs are only submitted by y
+    fn delete_confirmed(&self) -> bool {
+        self.confirm_dialog
+            .as_ref()
+            .map(|d| d.kind == ConfirmKind::YesNo || d.accepts(&self.confirm_buffer))
+            .unwrap_or(false)
+    }
+
+    /// Repo awaiting delete confirmation, looked up by the ID captured at prompt time
+    pub fn pending_delete_repo(&self) -> Option<&RepoRow> {
+        self.pending_delete_id
+            .as_ref()
+            .and_then(|id| self.repos.iter().find(|r| &r.id == id))
+    }
+
+    /// Remote deletes are always confirmed, whatever `confirm_level` says
     pub fn start_delete_remote_confirm(&mut self) {
         let can_delete = self.get_selected_repo()
             .map(|r| r.github_url.is_some() && r.is_member)
             .unwrap_or(false);
         if can_delete {
-            self.input_mode = InputMode::ConfirmDelete;
-            self.pending_delete = Some(DeleteType::RemoteRepo);
-            self.confirm_buffer.clear();
+            self.pending_delete_id = self.get_selected_repo().map(|r| r.id.clone());
+            let target = self.get_selected_repo().map(|r| r.name.clone()).unwrap_or_default();
+            let dialog = ConfirmDialog::typed(
+                " Confirm Delete Remote ",
+                vec![
+                    format!("DELETE {} FROM GITHUB?", target),
+                    String::new(),
+                    "This removes the repo, its issues and its history on GitHub.".to_string(),
+                ],
+                Some("yes".to_string()),
+            );
+            self.open_typed_confirm(DeleteType::RemoteRepo, dialog);
         }
     }
 
     pub fn delete_local_repo(&mut self) {
-        if self.confirm_buffer.to_lowercase() == "y" || self.confirm_buffer.to_lowercase() == "yes" {
-            // Check if we're deleting marked items
-            let marked = self.marked_local_repos();
-            if !marked.is_empty() {
-                let count = marked.len();
-                self.set_status(format!("Deleting {} repos...", count));
-                let tx = self.task_tx.clone();
-                tokio::spawn(async move {
-                    let mut success_count = 0;
-                    let mut fail_count = 0;
-                    let mut errors = Vec::new();
-                    for (name, path) in marked {
-                        let result = tokio::fs::remove_dir_all(&path).await;
-                        if result.is_ok() {
-                            success_count += 1;
-                        } else {
-                            fail_count += 1;
-                            errors.push(format!("{}: {}", name, result.err().unwrap()));
-                        }
-                    }
-                    let msg = if fail_count == 0 {
-                        format!("Deleted {} repos", success_count)
+        if self.delete_confirmed() {
+            self.run_local_delete();
+        } else {
+            self.close_popup();
+        }
+        self.pending_delete = None;
+    }
+
+    /// Delete the marked clones, or the repo captured in `pending_delete_id`
+    fn run_local_delete(&mut self) {
+        let marked = self.marked_local_repos();
+        // Check if we're deleting marked items
+        if !marked.is_empty() {
+            if let Some((name, path)) = marked.iter().find(|(_, p)| local::is_protected_dir(Path::new(p), &self.local_roots)) {
+                self.set_status_error(format!("Refusing to delete {}: {} holds the scan root or home", name, path));
+                self.close_popup();
+                return;
+            }
+            let count = marked.len();
+            if self.skip_for_dry_run(format!("delete {} repos", count)) {
+                self.close_popup();
+                return;
+            }
+            self.set_status(format!("Deleting {} repos...", count));
+            let tx = self.task_tx.clone();
+            tokio::spawn(async move {
+                let mut success_count = 0;
+                let mut fail_count = 0;
+                let mut errors = Vec::new();
+                for (name, path) in marked {
+                    let result = tokio::fs::remove_dir_all(&path).await;
+                    if result.is_ok() {
+                        success_count += 1;
                     } else {
-                        format!("Deleted {}/{} (E: view errors)", success_count, success_count + fail_count)
-                    };
+                        fail_count += 1;
+                        errors.push(format!("{}: {}", name, result.err().unwrap()));
+                    }
+                }
+                let msg = if fail_count == 0 {
+                    format!("Deleted {} repos", success_count)
+                } else {
+                    format!("Deleted {}/{} (E: view errors)", success_count, success_count + fail_count)
+                };
+                let _ = tx.send(TaskResult {
+                    success: fail_count == 0,
+                    message: msg,
+                    stderr: if errors.is_empty() { None } else { Some(errors.join("\n")) },
+                    operation: format!("delete {} repos", count),
+                    invalidates_github_cache: false,
+                }).await;
+            });
+            self.clear_marks();
+            self.close_popup();
+        } else {
+            // Single repo delete - target captured when the confirm opened
+            let info = self.pending_delete_repo().map(|r| (r.name.clone(), r.local_path.clone()));
+            if let Some((name, Some(path))) = info {
+                if local::is_protected_dir(Path::new(&path), &self.local_roots) {
+                    self.set_status_error(format!("Refusing to delete {}: {} holds the scan root or home", name, path));
+                    self.close_popup();
+                    return;
+                }
+                if self.skip_for_dry_run(format!("delete {}", path)) {
+                    self.close_popup();
+                    return;
+                }
+                self.set_status(format!("Deleting {}...", name));
+                let tx = self.task_tx.clone();
+                let op = format!("delete local {}", name);
+                tokio::spawn(async move {
+                    let result = tokio::fs::remove_dir_all(&path).await;
                     let _ = tx.send(TaskResult {
-                        success: fail_count == 0,
-                        message: msg,
-                        stderr: if errors.is_empty() { None } else { Some(errors.join("\n")) },
-                        operation: format!("delete {} repos", count),
+                        success: result.is_ok(),
+                        message: if result.is_ok() {
+                            format!("Deleted {}", name)
+                        } else {
+                            format!("Failed to delete {}", name)
+                        },
+                        stderr: result.err().map(|e| e.to_string()),
+                        operation: op,
                         invalidates_github_cache: false,
                     }).await;
                 });
-                self.clear_marks();
                 self.close_popup();
-            } else {
-                // Single repo delete
-                let info = self.get_selected_repo().map(|r| (r.name.clone(), r.local_path.clone()));
-                if let Some((name, Some(path))) = info {
-                    self.set_status(format!("Deleting {}...", name));
-                    let tx = self.task_tx.clone();
-                    let op = format!("delete local {}", name);
-                    tokio::spawn(async move {
-                        let result = tokio::fs::remove_dir_all(&path).await;
-                        let _ = tx.send(TaskResult {
-                            success: result.is_ok(),
-                            message: if result.is_ok() {
-                                format!("Deleted {}", name)
-                            } else {
-                                format!("Failed to delete {}", name)
-                            },
-                            stderr: result.err().map(|e| e.to_string()),
-                            operation: op,
-                            invalidates_github_cache: false,
-                        }).await;
-                    });
-                    self.close_popup();
-                }
             }
-        } else {
-            self.close_popup();
         }
-        self.pending_delete = None;
     }
 
     pub fn delete_remote_repo(&mut self) {
-        if self.confirm_buffer.to_lowercase() == "y" || self.confirm_buffer.to_lowercase() == "yes" {
-            let info = self.get_selected_repo().and_then(|r| {
+        if self.delete_confirmed() {
+            let info = self.pending_delete_repo().and_then(|r| {
                 r.owner.clone().map(|o| format!("{}/{}", o, r.name))
             });
             if let Some(name_with_owner) = info {
+                if self.skip_for_dry_run(format!("delete remote {}", name_with_owner)) {
+                    self.close_popup();
+                    return;
+                }
                 self.set_status(format!("Deleting remote {}...", name_with_owner));
                 let tx = self.task_tx.clone();
                 let name = name_with_owner.clone();
@@ -1527,13 +3270,14 @@ impl App {
         self.pending_delete = None;
     }
 
+    /// Ask to move the selected repo into its ghq path
     pub fn reorganize_to_ghq(&mut self) {
         let info = self.get_selected_repo().map(|r| {
             (
                 r.name.clone(),
                 r.local_path.clone(),
-                r.expected_ghq_path(&self.local_root),
-                r.follows_ghq(&self.local_root),
+                r.expected_ghq_path(&self.scan_roots, self.config.ghq_layout),
+                r.follows_ghq(&self.scan_roots, self.config.ghq_check()),
             )
         });
 
@@ -1542,87 +3286,253 @@ impl App {
             let src_canonical = Path::new(&current_path).canonicalize();
             let dst_canonical = Path::new(&expected_path).canonicalize();
 
-            // If both paths canonicalize to the same location, repo is already in place
-            if let (Ok(src), Ok(dst)) = (&src_canonical, &dst_canonical) {
-                if src == dst {
+            // If both paths canonicalize to the same location, repo is already in place,
+            // unless strict_case wants the on-disk case fixed (case-insensitive filesystem)
+            let same_dir = matches!((&src_canonical, &dst_canonical), (Ok(src), Ok(dst)) if src == dst);
+            if same_dir {
+                let case_differs = src_canonical.as_ref().is_ok_and(|src| src.as_path() != Path::new(&expected_path));
+                if !(self.config.strict_ghq_case && case_differs) {
                     self.set_status(format!("{} is already in ghq path", name));
                     return;
                 }
             }
 
             // If destination already exists (but isn't the same as source), don't overwrite
-            if dst_canonical.is_ok() {
+            if dst_canonical.is_ok() && !same_dir {
                 self.set_status(format!("Destination already exists for {}", name));
                 return;
             }
 
-            self.set_status(format!("Reorganizing {}...", name));
-            let tx = self.task_tx.clone();
-            let op = format!("reorganize {}", name);
-            tokio::spawn(async move {
-                let result = async {
-                    let src = Path::new(&current_path);
-                    let dst = Path::new(&expected_path);
+            let content = vec![
+                format!("Move {} to its ghq path:", name),
+                String::new(),
+                format!("  from {}", current_path),
+                format!("  to   {}", expected_path),
+                String::new(),
+                "Press y or Enter to move, Esc to cancel".to_string(),
+            ];
+            self.open_confirm(ConfirmAction::Reorganize { name, from: current_path, to: expected_path }, content);
+        }
+    }
 
-                    // Create parent directories
-                    if let Some(parent) = dst.parent() {
-                        tokio::fs::create_dir_all(parent).await?;
-                    }
+    /// Move a repo into its ghq path in the background
+    fn run_reorganize(&mut self, name: String, from: String, to: String) {
+        if self.skip_for_dry_run(format!("move {} to {}", from, to)) {
+            return;
+        }
+        self.set_status(format!("Reorganizing {}...", name));
+        let tx = self.task_tx.clone();
+        let op = format!("reorganize {}", name);
+        tokio::spawn(async move {
+            let result = local::move_dir(Path::new(&from), Path::new(&to)).await;
 
-                    // Try simple rename first (works on same filesystem)
-                    match tokio::fs::rename(src, dst).await {
-                        Ok(()) => Ok(()),
-                        Err(e) => {
-                            // If rename fails (cross-device or target exists), try recursive copy
-                            if e.kind() == std::io::ErrorKind::Other
-                                || e.kind() == std::io::ErrorKind::AlreadyExists
-                                || e.raw_os_error() == Some(18) // EXDEV - cross-device link
-                                || e.raw_os_error() == Some(39) // ENOTEMPTY
-                            {
-                                // Recursive copy using system cp command for reliability
-                                let status = tokio::process::Command::new("cp")
-                                    .args(["-r", &current_path, &expected_path])
-                                    .status()
-                                    .await?;
-
-                                if status.success() {
-                                    // Remove original after successful copy
-                                    tokio::fs::remove_dir_all(src).await?;
-                                    Ok(())
-                                } else {
-                                    Err(std::io::Error::new(
-                                        std::io::ErrorKind::Other,
-                                        "cp command failed",
-                                    ))
-                                }
-                            } else {
-                                Err(e)
-                            }
-                        }
-                    }
-                }.await;
+            let _ = tx.send(TaskResult {
+                success: result.is_ok(),
+                message: if result.is_ok() {
+                    format!("Moved {} to ghq path", name)
+                } else {
+                    "Move failed (E: view errors)".to_string()
+                },
+                stderr: result.err().map(|e| e.to_string()),
+                operation: op,
+                invalidates_github_cache: false, // Local filesystem operation
+            }).await;
+        });
+    }
 
-                let _ = tx.send(TaskResult {
-                    success: result.is_ok(),
-                    message: if result.is_ok() {
-                        format!("Moved {} to ghq path", name)
-                    } else {
-                        "Move failed (E: view errors)".to_string()
-                    },
-                    stderr: result.err().map(|e| e.to_string()),
-                    operation: op,
-                    invalidates_github_cache: false, // Local filesystem operation
-                }).await;
+    /// Ask to rename the local folder to the GitHub repo name
+    pub fn start_rename_folder(&mut self) {
+        let info = self.get_selected_repo().and_then(|r| {
+            let target = r.renamed_folder_path()?;
+            r.local_path.clone().map(|p| (r.name.clone(), p, target))
+        });
+        if let Some((name, from, target)) = info {
+            if target.exists() {
+                self.set_status_error(format!("Can't rename: {} already exists", target.display()));
+                return;
+            }
+            let to = target.to_string_lossy().to_string();
+            let content = vec![
+                format!("Rename local folder of {}:", name),
+                String::new(),
+                format!("  from {}", from),
+                format!("  to   {}", to),
+                String::new(),
+                "Press y or Enter to rename, Esc to cancel".to_string(),
+            ];
+            self.open_confirm(ConfirmAction::RenameFolder { name, from, to }, content);
+        }
+    }
+
+    /// Move a local folder in the background (same move logic as ghq reorganize)
+    fn rename_folder(&mut self, name: String, from: String, to: String) {
+        if self.skip_for_dry_run(format!("move {} to {}", from, to)) {
+            return;
+        }
+        self.set_status(format!("Renaming folder of {}...", name));
+        let tx = self.task_tx.clone();
+        let op = format!("rename folder {}", name);
+        tokio::spawn(async move {
+            let result = local::move_dir(Path::new(&from), Path::new(&to)).await;
+            let _ = tx.send(TaskResult {
+                success: result.is_ok(),
+                message: if result.is_ok() {
+                    format!("Renamed folder to {}", name)
+                } else {
+                    "Rename failed (E: view errors)".to_string()
+                },
+                stderr: result.err().map(|e| e.to_string()),
+                operation: op,
+                invalidates_github_cache: false, // Local filesystem operation
+            }).await;
+        });
+    }
+
+    /// Offer to re-add origin for a local-only repo whose name matches a cached GitHub repo
+    pub fn start_relink_remote(&mut self) {
+        let info = self.get_selected_repo().and_then(|r| {
+            if r.is_local_only() && r.has_git {
+                r.local_path.clone().map(|p| (r.name.clone(), p))
+            } else {
+                None
+            }
+        });
+
+        if let Some((name, path)) = info {
+            // Prefer the user's own repo when several owners share the name
+            let username = self.github_username.clone().unwrap_or_default();
+            let candidate = self.github_cache.as_ref().and_then(|cache| {
+                let mut matches: Vec<&github::GitHubRepoInfo> = cache.repos
+                    .iter()
+                    .filter(|gh| gh.name.eq_ignore_ascii_case(&name))
+                    .collect();
+                matches.sort_by_key(|gh| !gh.owner.eq_ignore_ascii_case(&username));
+                matches.first().map(|gh| (gh.owner.clone(), gh.name.clone(), gh.url.clone()))
             });
+
+            match candidate {
+                Some((owner, gh_name, url)) => {
+                    let content = vec![
+                        format!("Set origin of {} to:", name),
+                        String::new(),
+                        format!("  {}/{}", owner, gh_name),
+                        format!("  {}", url),
+                        String::new(),
+                        format!("Local: {}", path),
+                        String::new(),
+                        "Press y or Enter to link, Esc to cancel".to_string(),
+                    ];
+                    self.open_confirm(ConfirmAction::Relink { name, path, url }, content);
+                }
+                None => self.set_status_error(format!("No GitHub repo named {}", name)),
+            }
+        }
+    }
+
+    /// Show a y/Enter confirmation popup for an action
+    fn open_confirm(&mut self, action: ConfirmAction, content: Vec<String>) {
+        self.pending_confirm = Some(action);
+        self.confirm_dialog = Some(ConfirmDialog::yes_no(" Confirm ", content));
+        // The popup only routes keys; the dialog carries the text
+        self.popup = Some(Popup::new(PopupType::Confirm, Vec::new()));
+    }
+
+    /// Run the action from the confirm popup
+    pub fn confirm_pending_action(&mut self) {
+        let action = self.pending_confirm.take();
+        self.close_popup();
+        match action {
+            Some(ConfirmAction::Relink { name, path, url }) => self.relink_remote(name, path, url),
+            Some(ConfirmAction::ToggleVisibility { id }) => self.toggle_private_for(&id),
+            Some(ConfirmAction::ToggleArchive { id }) => self.toggle_archived_for(&id),
+            Some(ConfirmAction::UnshallowThenQuicksync { id }) => self.unshallow_then_quicksync(&id),
+            Some(ConfirmAction::RenameFolder { name, from, to }) => self.rename_folder(name, from, to),
+            Some(ConfirmAction::Reorganize { name, from, to }) => self.run_reorganize(name, from, to),
+            Some(ConfirmAction::ForceDeleteBranch { name, path, branch }) => self.force_delete_branch(name, path, branch),
+            Some(ConfirmAction::RebaseFork { name, path, parent, branch }) => self.rebase_fork(name, path, parent, branch),
+            Some(ConfirmAction::DeleteFolder { id }) => {
+                self.pending_delete_id = Some(id);
+                self.run_local_delete();
+            }
+            // Quitting ends the event loop, so main handles it before calling here
+            Some(ConfirmAction::Quit) | None => {}
         }
     }
 
+    /// Whether the app may exit now; with warn_dirty_on_quit set, dirty repos open a confirm instead
+    pub fn request_quit(&mut self) -> bool {
+        if !self.config.warn_dirty_on_quit {
+            return true;
+        }
+        let dirty: Vec<String> = self.repos.iter()
+            .filter(|r| r.git_status.as_ref().is_some_and(|s| s.is_dirty()))
+            .map(|r| r.local_path.clone().unwrap_or_else(|| r.name.clone()))
+            .collect();
+        if dirty.is_empty() {
+            return true;
+        }
+        let noun = if dirty.len() == 1 { "repo has" } else { "repos have" };
+        let mut content = vec![
+            format!("{} {} uncommitted changes — quit anyway?", dirty.len(), noun),
+            String::new(),
+        ];
+        content.extend(dirty.iter().map(|name| format!("  {}", name)));
+        content.push(String::new());
+        content.push("Press y or Enter to quit, Esc to cancel".to_string());
+        self.open_confirm(ConfirmAction::Quit, content);
+        false
+    }
+
+    /// Point a local repo's origin at the given URL in the background
+    fn relink_remote(&mut self, name: String, path: String, url: String) {
+        if self.skip_for_dry_run(format!("set origin of {} to {}", name, url)) {
+            return;
+        }
+        self.set_status(format!("Linking {} to {}...", name, url));
+        let tx = self.task_tx.clone();
+        let op = format!("link origin {}", name);
+        tokio::spawn(async move {
+            let result = git::set_origin(&path, &url).await;
+            let _ = tx.send(TaskResult {
+                success: result.success,
+                message: if result.success {
+                    format!("Linked {} to origin", name)
+                } else {
+                    "Link origin failed (E: view errors)".to_string()
+                },
+                stderr: if result.success { None } else { Some(result.stderr) },
+                operation: op,
+                invalidates_github_cache: false, // Local git config change
+            }).await;
+        });
+    }
+
     pub fn toggle_private(&mut self) {
-        let info = self.get_selected_repo().and_then(|r| {
+        let info = self.get_selected_repo().map(|r| (r.id.clone(), r.name.clone(), r.is_private));
+        if let Some((id, name, is_private)) = info {
+            if self.config.confirm_level == ConfirmLevel::All {
+                let target = if is_private { "public" } else { "private" };
+                let content = vec![
+                    format!("Make {} {}?", name, target),
+                    String::new(),
+                    "Press y or Enter to confirm, Esc to cancel".to_string(),
+                ];
+                self.open_confirm(ConfirmAction::ToggleVisibility { id }, content);
+            } else {
+                self.toggle_private_for(&id);
+            }
+        }
+    }
+
+    fn toggle_private_for(&mut self, id: &str) {
+        let info = self.repos.iter().find(|r| r.id == id).and_then(|r| {
             r.owner.clone().map(|o| (format!("{}/{}", o, r.name), r.is_private, r.is_archived))
         });
         if let Some((name_with_owner, is_private, is_archived)) = info {
             let new_visibility = if is_private { "public" } else { "private" };
+            if self.skip_for_dry_run(format!("set {} to {}", name_with_owner, new_visibility)) {
+                return;
+            }
             let status_msg = if is_archived {
                 format!("Unarchiving, setting {} to {}, then re-archiving...", name_with_owner, new_visibility)
             } else {
@@ -1680,7 +3590,35 @@ impl App {
     }
 
     pub fn toggle_archived(&mut self) {
-        let info = self.get_selected_repo().and_then(|r| {
+        let info = self.get_selected_repo().map(|r| (r.id.clone(), r.name.clone(), r.is_archived));
+        if let Some((id, name, is_archived)) = info {
+            if self.config.confirm_level == ConfirmLevel::All {
+                let content = if is_archived {
+                    vec![
+                        format!("Unarchive {}?", name),
+                        String::new(),
+                        "Visibility is unchanged; push and sync work again.".to_string(),
+                        String::new(),
+                        "Press y or Enter to unarchive, Esc to cancel".to_string(),
+                    ]
+                } else {
+                    vec![
+                        format!("Archive {}?", name),
+                        String::new(),
+                        "The repo becomes read-only on GitHub.".to_string(),
+                        String::new(),
+                        "Press y or Enter to archive, Esc to cancel".to_string(),
+                    ]
+                };
+                self.open_confirm(ConfirmAction::ToggleArchive { id }, content);
+            } else {
+                self.toggle_archived_for(&id);
+            }
+        }
+    }
+
+    fn toggle_archived_for(&mut self, id: &str) {
+        let info = self.repos.iter().find(|r| r.id == id).and_then(|r| {
             if r.is_member {
                 r.owner.clone().map(|o| (format!("{}/{}", o, r.name), r.is_archived))
             } else {
@@ -1688,6 +3626,9 @@ impl App {
             }
         });
         if let Some((name_with_owner, is_archived)) = info {
+            if self.skip_for_dry_run(format!("{} {}", if is_archived { "unarchive" } else { "archive" }, name_with_owner)) {
+                return;
+            }
             let action = if is_archived { "Unarchiving" } else { "Archiving" };
             self.set_status(format!("{} {}...", action, name_with_owner));
             let tx = self.task_tx.clone();
@@ -1721,8 +3662,11 @@ impl App {
             }
         });
         if let Some(id) = info {
-            let clone_path = format!("{}/gists/{}", self.local_root, id);
-            let display_id = id[..8.min(id.len())].to_string();
+            let clone_path = Path::new(self.primary_root()).join("gists").join(&id).to_string_lossy().to_string();
+            let display_id = short_gist_id(&id);
+            if self.skip_for_dry_run(format!("clone gist {}", display_id)) {
+                return;
+            }
             self.set_status(format!("Cloning gist {}...", display_id));
             let tx = self.task_tx.clone();
             let op = format!("clone gist {}", display_id);
@@ -1744,61 +3688,81 @@ impl App {
     }
 
     pub fn start_gist_delete_confirm(&mut self) {
-        if self.get_selected_gist().is_some() {
-            self.input_mode = InputMode::ConfirmDelete;
-            self.pending_delete = Some(DeleteType::Gist);
-            self.confirm_buffer.clear();
+        if let Some((id, description)) = self.get_selected_gist().map(|g| (g.id.clone(), g.description.clone())) {
+            // Gists are deleted on GitHub, so this prompts whatever `confirm_level` says
+            self.pending_delete_id = Some(id);
+            let mut message = vec!["Delete this gist from GitHub? [y/n]".to_string()];
+            if !description.is_empty() {
+                message.push(String::new());
+                message.push(format!("  {}", description));
+            }
+            let dialog = ConfirmDialog::yes_no(" Confirm Delete Gist ", message);
+            self.open_typed_confirm(DeleteType::Gist, dialog);
         }
     }
 
     pub fn delete_gist(&mut self) {
-        if self.confirm_buffer.to_lowercase() == "y" || self.confirm_buffer.to_lowercase() == "yes" {
-            let id = self.get_selected_gist().map(|g| g.id.clone());
-            if let Some(id) = id {
-                let display_id = id[..8.min(id.len())].to_string();
-                self.set_status(format!("Deleting gist {}...", display_id));
-                let tx = self.task_tx.clone();
-                let op = format!("delete gist {}", display_id);
-                let gist_id = id.clone();
-                tokio::spawn(async move {
-                    let result = github::delete_gist(&gist_id).await;
-                    let _ = tx.send(TaskResult {
-                        success: result.success,
-                        message: if result.success {
-                            format!("Deleted gist {}", display_id)
-                        } else {
-                            format!("Failed to delete gist {} (E: view errors)", display_id)
-                        },
-                        stderr: Some(result.stderr),
-                        operation: op,
-                        invalidates_github_cache: true, // Gist deleted from GitHub
-                    }).await;
-                });
-                self.close_popup();
-            }
+        if self.delete_confirmed() {
+            self.run_gist_delete();
         } else {
             self.close_popup();
         }
         self.pending_delete = None;
     }
 
+    /// Delete the gist captured in `pending_delete_id`
+    fn run_gist_delete(&mut self) {
+        let id = self.pending_delete_id.clone();
+        if let Some(id) = id {
+            let display_id = short_gist_id(&id);
+            if self.skip_for_dry_run(format!("delete gist {}", display_id)) {
+                self.close_popup();
+                return;
+            }
+            self.set_status(format!("Deleting gist {}...", display_id));
+            let tx = self.task_tx.clone();
+            let op = format!("delete gist {}", display_id);
+            let gist_id = id.clone();
+            tokio::spawn(async move {
+                let result = github::delete_gist(&gist_id).await;
+                let _ = tx.send(TaskResult {
+                    success: result.success,
+                    message: if result.success {
+                        format!("Deleted gist {}", display_id)
+                    } else {
+                        format!("Failed to delete gist {} (E: view errors)", display_id)
+                    },
+                    stderr: Some(result.stderr),
+                    operation: op,
+                    invalidates_github_cache: true, // Gist deleted from GitHub
+                }).await;
+            });
+            self.close_popup();
+        }
+    }
+
     pub fn pull_gist(&mut self) {
         let info = self.get_selected_gist().and_then(|g| {
             g.local_path.clone().map(|p| (g.id.clone(), p))
         });
         if let Some((id, path)) = info {
-            let display_id = id[..8.min(id.len())].to_string();
+            let display_id = short_gist_id(&id);
+            if self.skip_for_dry_run(format!("pull gist {}", display_id)) {
+                return;
+            }
             self.set_status(format!("Pulling gist {}...", display_id));
             let tx = self.task_tx.clone();
             let op = format!("pull gist {}", display_id);
+            let on_progress = self.progress_reporter(&op);
             tokio::spawn(async move {
-                let result = git::pull(&path).await;
+                let (result, pulled) = git::pull_counted(&path, on_progress).await;
                 let _ = tx.send(TaskResult {
                     success: result.success,
-                    message: if result.success {
-                        format!("Pulled gist {}", display_id)
-                    } else {
-                        "Pull gist failed (E: view errors)".to_string()
+                    message: match (result.success, pulled) {
+                        (true, Some(0)) => format!("Gist {} already up to date", display_id),
+                        (true, Some(n)) => format!("Pulled gist {} (+{})", display_id, n),
+                        (true, None) => format!("Pulled gist {}", display_id),
+                        (false, _) => "Pull gist failed (E: view errors)".to_string(),
                     },
                     stderr: if result.success { None } else { Some(result.stderr) },
                     operation: op,
@@ -1813,7 +3777,10 @@ impl App {
             g.local_path.clone().map(|p| (g.id.clone(), p))
         });
         if let Some((id, path)) = info {
-            let display_id = id[..8.min(id.len())].to_string();
+            let display_id = short_gist_id(&id);
+            if self.skip_for_dry_run(format!("push gist {}", display_id)) {
+                return;
+            }
             self.set_status(format!("Pushing gist {}...", display_id));
             let tx = self.task_tx.clone();
             let op = format!("push gist {}", display_id);
@@ -1839,13 +3806,17 @@ impl App {
             g.local_path.clone().map(|p| (g.id.clone(), p))
         });
         if let Some((id, path)) = info {
-            let display_id = id[..8.min(id.len())].to_string();
+            let display_id = short_gist_id(&id);
+            if self.skip_for_dry_run(format!("sync gist {}", display_id)) {
+                return;
+            }
             self.set_status(format!("Syncing gist {}...", display_id));
             let tx = self.task_tx.clone();
             let op = format!("sync gist {}", display_id);
+            let on_progress = self.progress_reporter(&op);
             tokio::spawn(async move {
-                let fetch_res = git::fetch(&path).await;
-                let pull_res = git::pull(&path).await;
+                let fetch_res = git::fetch(&path, &on_progress).await;
+                let pull_res = git::pull(&path, &on_progress).await;
                 let push_res = git::push(&path).await;
                 let success = fetch_res.success && pull_res.success && push_res.success;
                 let stderr = if !success {
@@ -1872,130 +3843,709 @@ impl App {
         }
     }
 
-    /// Show upload form for a local-only repo
-    pub fn show_upload_form(&mut self) {
+    /// Show upload form for a local-only repo
+    pub fn show_upload_form(&mut self) {
+        let info = self.get_selected_repo().and_then(|r| {
+            if r.is_local_only() {
+                r.local_path.clone().map(|p| (r.name.clone(), p))
+            } else {
+                None
+            }
+        });
+        if let Some((name, path)) = info {
+            // Fetch orgs in background and update form when ready
+            let tx = self.task_tx.clone();
+            tokio::spawn(async move {
+                let orgs = github::get_user_orgs().await.unwrap_or_default();
+                // Send orgs as a special message - we'll parse it later
+                let _ = tx.send(TaskResult {
+                    success: true,
+                    message: format!("__ORGS__:{}", orgs.join(",")),
+                    stderr: None,
+                    operation: String::new(),
+                    invalidates_github_cache: false, // Not a real operation, just data fetch
+                }).await;
+            });
+
+            self.upload_form = Some(UploadFormState {
+                name,
+                description: String::new(),
+                private: self.config.default_upload_private,
+                orgs: Vec::new(), // Will be populated when orgs arrive
+                selected_org: 0,  // 0 = personal account
+                active_field: UploadField::Name,
+                local_path: path,
+            });
+            self.input_mode = InputMode::UploadForm;
+            self.popup = Some(Popup::new(PopupType::Upload, Vec::new()));
+        }
+    }
+
+    /// Owner the upload form will create the repo under (org login or the signed-in user)
+    pub fn upload_owner(&self) -> Option<String> {
+        let form = self.upload_form.as_ref()?;
+        if form.selected_org == 0 {
+            self.github_username.clone()
+        } else {
+            form.orgs.get(form.selected_org - 1).cloned()
+        }
+    }
+
+    /// Whether the cached GitHub repos already have the form's name under the chosen owner
+    pub fn upload_name_taken(&self) -> bool {
+        if let (Some(form), Some(owner), Some(cache)) = (&self.upload_form, self.upload_owner(), &self.github_cache) {
+            cache.repos.iter().any(|r| {
+                r.owner.eq_ignore_ascii_case(&owner) && r.name.eq_ignore_ascii_case(&form.name)
+            })
+        } else {
+            false
+        }
+    }
+
+    /// Submit the upload form
+    pub fn submit_upload_form(&mut self) {
+        if let Some(error) = self.upload_form.as_ref().and_then(|f| f.name_error()) {
+            self.set_status_error(format!("Invalid repo name: {}", error));
+            return;
+        }
+        if self.upload_name_taken() {
+            let owner = self.upload_owner().unwrap_or_default();
+            self.set_status_error(format!("{} already has a repo with that name", owner));
+            return;
+        }
+        if let Some(form) = self.upload_form.take() {
+            let org = if form.selected_org == 0 {
+                None
+            } else {
+                form.orgs.get(form.selected_org - 1).cloned()
+            };
+            if self.config.last_upload_owner != org {
+                self.config.last_upload_owner = org.clone();
+                self.config.save();
+            }
+
+            let opts = github::CreateRepoOptions {
+                name: form.name.clone(),
+                path: form.local_path.clone(),
+                description: if form.description.is_empty() { None } else { Some(form.description) },
+                private: form.private,
+                org,
+            };
+
+            if self.skip_for_dry_run(format!("create GitHub repo {}", opts.name)) {
+                self.close_popup();
+                return;
+            }
+            self.set_status(format!("Creating GitHub repo {}...", opts.name));
+            let tx = self.task_tx.clone();
+            let name = opts.name.clone();
+            let op = format!("create repo {}", name);
+            tokio::spawn(async move {
+                let result = github::create_repo(&opts).await;
+                let _ = tx.send(TaskResult {
+                    success: result.success,
+                    message: if result.success {
+                        format!("Created {}", name)
+                    } else {
+                        "Create repo failed (E: view errors)".to_string()
+                    },
+                    stderr: if result.success { None } else { Some(result.stderr) },
+                    operation: op,
+                    invalidates_github_cache: true, // New repo created on GitHub
+                }).await;
+            });
+
+            self.close_popup();
+        }
+    }
+
+    /// Cancel upload form
+    pub fn cancel_upload_form(&mut self) {
+        self.upload_form = None;
+        self.close_popup();
+    }
+
+    /// Navigate to next field in upload form
+    pub fn upload_form_next_field(&mut self) {
+        if let Some(ref mut form) = self.upload_form {
+            form.active_field = form.active_field.next();
+        }
+    }
+
+    /// Navigate to previous field in upload form
+    pub fn upload_form_prev_field(&mut self) {
+        if let Some(ref mut form) = self.upload_form {
+            form.active_field = form.active_field.prev();
+        }
+    }
+
+    /// Toggle private field in upload form
+    pub fn upload_form_toggle_private(&mut self) {
+        if let Some(ref mut form) = self.upload_form {
+            form.private = !form.private;
+        }
+    }
+
+    /// Cycle org selection in upload form
+    pub fn upload_form_next_org(&mut self) {
+        if let Some(ref mut form) = self.upload_form {
+            let max = form.orgs.len(); // 0 is personal, then orgs
+            form.selected_org = (form.selected_org + 1) % (max + 1);
+        }
+    }
+
+    pub fn upload_form_prev_org(&mut self) {
+        if let Some(ref mut form) = self.upload_form {
+            let max = form.orgs.len();
+            if form.selected_org == 0 {
+                form.selected_org = max;
+            } else {
+                form.selected_org -= 1;
+            }
+        }
+    }
+
+    /// Open the commit message input for the selected repo
+    pub fn start_commit(&mut self) {
+        let target = self.get_selected_repo()
+            .filter(|r| r.has_git)
+            .and_then(|r| r.local_path.clone().map(|p| (r.name.clone(), p)));
+        self.open_commit_form(target, true);
+    }
+
+    /// Open the commit message input committing only the files staged in the files popup
+    pub fn start_commit_staged(&mut self) {
+        if !self.file_changes.iter().any(|c| c.is_staged()) {
+            self.set_status_error("Nothing staged (Space: stage file)");
+            return;
+        }
+        self.popup = None;
+        // Commit in the repo the popup was opened for, not whatever is selected now
+        self.open_commit_form(self.files_repo.clone(), false);
+    }
+
+    fn open_commit_form(&mut self, target: Option<(String, String)>, stage_all: bool) {
+        let info = target.map(|(name, path)| {
+            let has_commits = self.repos.iter().chain(&self.starred)
+                .find(|r| r.local_path.as_deref() == Some(path.as_str()))
+                .and_then(|r| r.git_status.as_ref())
+                .map(|s| s.has_commits)
+                .unwrap_or(false);
+            (name, path, has_commits)
+        });
+        if let Some((name, path, has_commits)) = info {
+            self.commit_form = Some(CommitFormState {
+                message: String::new(),
+                amend: false,
+                can_amend: has_commits,
+                stage_all,
+                push: false,
+                repo_name: name,
+                local_path: path,
+            });
+            self.input_mode = InputMode::Commit;
+        }
+    }
+
+    /// Toggle amend in the commit input (only when HEAD exists)
+    pub fn commit_form_toggle_amend(&mut self) {
+        if let Some(ref mut form) = self.commit_form {
+            if form.can_amend {
+                form.amend = !form.amend;
+                // Pushing an amended commit would need a force push
+                form.push &= !form.amend;
+            }
+        }
+    }
+
+    /// Toggle pushing after the commit (not available while amending)
+    pub fn commit_form_toggle_push(&mut self) {
+        if let Some(ref mut form) = self.commit_form {
+            if !form.amend {
+                form.push = !form.push;
+            }
+        }
+    }
+
+    /// Submit the commit input
+    pub fn submit_commit(&mut self) {
+        if let Some(form) = self.commit_form.take() {
+            let message = form.message.trim().to_string();
+            if message.is_empty() && !form.amend {
+                // Keep the form open so the user can enter a message
+                self.commit_form = Some(form);
+                self.set_status_error("Commit message required");
+                return;
+            }
+
+            let name = form.repo_name;
+            let path = form.local_path;
+            let amend = form.amend;
+            let stage_all = form.stage_all;
+            let push = form.push && !amend;
+            let verb = match (amend, push) {
+                (true, _) => "amend",
+                (false, true) => "commit and push",
+                (false, false) => "commit",
+            };
+            if self.skip_for_dry_run(format!("{} {}", verb, name)) {
+                self.input_mode = InputMode::Normal;
+                return;
+            }
+            let action = if amend { "Amending" } else { "Committing" };
+            self.set_status(format!("{} {}...", action, name));
+            let tx = self.task_tx.clone();
+            let op = format!("{} {}", verb, name);
+            tokio::spawn(async move {
+                let committed = git::commit(&path, &message, amend, stage_all).await;
+                let result = if committed.success && push {
+                    git::push(&path).await
+                } else {
+                    committed.clone()
+                };
+                let _ = tx.send(TaskResult {
+                    success: result.success,
+                    message: match (committed.success, result.success) {
+                        (true, true) if push => format!("Committed and pushed {}", name),
+                        (true, true) => format!("{} {}", if amend { "Amended" } else { "Committed" }, name),
+                        (true, false) => format!("Committed {}, push failed (E: view errors)", name),
+                        (false, _) => "Commit failed (E: view errors)".to_string(),
+                    },
+                    stderr: if result.success { None } else { Some(result.stderr) },
+                    operation: op,
+                    invalidates_github_cache: false, // Local git operation
+                }).await;
+            });
+
+            self.input_mode = InputMode::Normal;
+        }
+    }
+
+    /// Show recent commits of the selected repo
+    pub fn show_log_popup(&mut self) {
+        let info = self.get_selected_repo().and_then(|r| {
+            if r.has_git {
+                r.local_path.clone().map(|p| (r.name.clone(), p))
+            } else {
+                None
+            }
+        });
+        if let Some((name, path)) = info {
+            self.set_status(format!("Reading log of {}...", name));
+            self.load_in_background(async move {
+                let commits = git::recent_commits(&path, LOG_COMMIT_COUNT).await;
+                move |app: &mut App| {
+                    app.clear_status();
+                    app.apply_log(name, commits);
+                }
+            });
+        }
+    }
+
+    /// Open the log popup from commits read in the background
+    fn apply_log(&mut self, name: String, commits: Result<Vec<git::CommitSummary>>) {
+        match commits {
+            Ok(commits) if self.can_open_loaded_popup() => {
+                let mut content = vec![
+                    format!("Recent commits in {}:", name),
+                    String::new(),
+                ];
+                if commits.is_empty() {
+                    content.push("No commits yet".to_string());
+                }
+                // Stored as "HASH|AUTHOR|TIME|SUBJECT" and styled by the renderer
+                content.extend(commits.iter().map(|c| {
+                    format!("{}|{}|{}|{}", c.hash, c.author, c.relative_time, c.subject)
+                }));
+                self.popup = Some(Popup::new(PopupType::Log, content));
+            }
+            Ok(_) => {} // Another popup or input opened meanwhile
+            Err(e) => {
+                self.error_log.push(ErrorLogEntry::new(format!("log {}", name), e.to_string()));
+                self.set_status_error("Failed to read log (E: view errors)");
+            }
+        }
+    }
+
+    /// Show the commits a fork has that upstream lacks, and vice versa
+    pub fn show_fork_diff(&mut self) {
+        let info = self.get_selected_repo().and_then(|r| {
+            let owner = r.owner.clone()?;
+            let parent = r.fork_parent.clone()?;
+            let branch = r.default_branch.clone()?;
+            let parent_branch = r.parent_default_branch.clone()?;
+            Some((owner, r.name.clone(), parent, branch, parent_branch))
+        });
+        if let Some((owner, name, parent, branch, parent_branch)) = info {
+            self.set_status(format!("Comparing {} with {}...", name, parent));
+            // Both directions are fetched together, off the input loop
+            self.load_in_background(async move {
+                let head = format!("{}:{}", owner, branch);
+                let (ahead, behind) = tokio::join!(
+                    github::compare_commits(&parent, &parent_branch, &head),
+                    github::compare_commits(&parent, &head, &parent_branch),
+                );
+                move |app: &mut App| {
+                    app.clear_status();
+                    match (ahead, behind) {
+                        (Ok(ahead), Ok(behind)) if app.can_open_loaded_popup() => {
+                            let mut content = vec![
+                                format!("{}/{} vs {}", owner, name, parent),
+                                String::new(),
+                            ];
+                            // Stored as "HASH|AUTHOR|TIME|SUBJECT" like the log popup
+                            for (label, commits) in [("Ahead of upstream", ahead), ("Behind upstream", behind)] {
+                                content.push(format!("{} ({}):", label, commits.len()));
+                                content.extend(commits.iter().map(|c| {
+                                    format!("{}|{}|{}|{}", c.sha, c.author, c.date, c.subject)
+                                }));
+                                content.push(String::new());
+                            }
+                            app.popup = Some(Popup::new(PopupType::ForkDiff, content));
+                        }
+                        (Ok(_), Ok(_)) => {} // Another popup or input opened meanwhile
+                        (Err(e), _) | (_, Err(e)) => {
+                            app.error_log.push(ErrorLogEntry::new(format!("fork diff {}", name), e.to_string()));
+                            app.set_status_error("Failed to compare with upstream (E: view errors)");
+                        }
+                    }
+                }
+            });
+        } else if self.get_selected_repo().is_some() {
+            self.set_status_error("Not a fork with a known upstream");
+        }
+    }
+
+    /// Confirm rebasing a diverged fork's commits onto the upstream default branch
+    pub fn start_rebase_fork(&mut self) {
+        if self.refuse_if_archived("rebase") {
+            return;
+        }
+        let info = self.get_selected_repo().map(|r| {
+            (
+                r.name.clone(),
+                r.local_path.clone(),
+                r.fork_parent.clone(),
+                r.parent_default_branch.clone(),
+                r.fork_ahead.unwrap_or(0),
+                r.fork_behind.unwrap_or(0),
+                r.git_status.as_ref().is_some_and(|s| s.is_dirty()),
+                r.is_shallow,
+                // Ahead/behind compare the fork's default branch, so only that one may be rebased
+                r.git_status.as_ref().map(|s| s.branch.clone()),
+                r.default_branch.clone().or_else(|| r.parent_default_branch.clone()),
+            )
+        });
+        if let Some((name, path, parent, branch, ahead, behind, dirty, shallow, current, fork_branch)) = info {
+            let (path, parent, branch) = match (path, parent, branch) {
+                (Some(path), Some(parent), Some(branch)) => (path, parent, branch),
+                (None, _, _) => {
+                    self.set_status_error(format!("{} is not cloned locally", name));
+                    return;
+                }
+                _ => {
+                    self.set_status_error("Not a fork with a known upstream");
+                    return;
+                }
+            };
+            if ahead == 0 || behind == 0 {
+                self.set_status_error(format!("{} has not diverged from upstream (↑{} ↓{})", name, ahead, behind));
+            } else if current != fork_branch {
+                self.set_status_error(format!(
+                    "{} is on {}, not {}; check out the default branch to rebase",
+                    name,
+                    current.unwrap_or_default(),
+                    fork_branch.unwrap_or_default()
+                ));
+            } else if dirty {
+                self.set_status_error(format!("{} has uncommitted changes; commit or stash before rebasing", name));
+            } else if shallow {
+                self.set_status_error(format!("{} is shallow; unshallow (U) before rebasing", name));
+            } else {
+                let content = vec![
+                    format!("Rebase {} onto {}/{}?", name, parent, branch),
+                    String::new(),
+                    format!("Replays your {} commit(s) on top of upstream's {}.", ahead, behind),
+                    "Conflicts abort the rebase and leave the branch unchanged.".to_string(),
+                    "Publishing afterwards needs git push --force-with-lease.".to_string(),
+                    String::new(),
+                    "Press y or Enter to rebase, Esc to cancel".to_string(),
+                ];
+                self.open_confirm(ConfirmAction::RebaseFork { name, path, parent, branch }, content);
+            }
+        }
+    }
+
+    /// Rebase onto upstream in the background
+    fn rebase_fork(&mut self, name: String, path: String, parent: String, branch: String) {
+        if self.skip_for_dry_run(format!("rebase {} onto {}/{}", name, parent, branch)) {
+            return;
+        }
+        self.set_status(format!("Rebasing {} onto upstream/{}...", name, branch));
+        let tx = self.task_tx.clone();
+        let op = format!("rebase {}", name);
+        tokio::spawn(async move {
+            let result = git::rebase_onto_upstream(&path, &parent, &branch).await;
+            let _ = tx.send(TaskResult {
+                success: result.success,
+                message: if result.success {
+                    format!("Rebased {} onto upstream/{} (push --force-with-lease to publish)", name, branch)
+                } else {
+                    format!("Rebase of {} aborted (E: view errors)", name)
+                },
+                stderr: if result.success { None } else { Some(result.stderr) },
+                operation: op,
+                invalidates_github_cache: false, // The fork on GitHub changes only once pushed
+            }).await;
+        });
+    }
+
+    /// Show the selected repo's full, untruncated local path
+    pub fn show_full_path(&mut self) {
+        let path = self.get_selected_repo().and_then(|r| r.local_path.clone());
+        if let Some(path) = path {
+            self.popup = Some(Popup::new(PopupType::Path, vec![path]));
+        }
+    }
+
+    /// Run a lookup off the input loop; the main loop applies the update it resolves to
+    fn load_in_background<F, U>(&self, load: F)
+    where
+        F: std::future::Future<Output = U> + Send + 'static,
+        U: FnOnce(&mut App) + Send + 'static,
+    {
+        let tx = self.update_tx.clone();
+        tokio::spawn(async move {
+            let update: AppUpdate = Box::new(load.await);
+            let _ = tx.send(update).await;
+        });
+    }
+
+    /// Whether a popup loaded in the background may still open (the user hasn't moved on)
+    fn can_open_loaded_popup(&self) -> bool {
+        self.popup.is_none() && self.input_mode == InputMode::Normal
+    }
+
+    /// Show the changed files of the selected repo for per-file staging
+    pub fn show_files_popup(&mut self) {
         let info = self.get_selected_repo().and_then(|r| {
-            if r.is_local_only() {
+            if r.has_git {
                 r.local_path.clone().map(|p| (r.name.clone(), p))
             } else {
                 None
             }
         });
         if let Some((name, path)) = info {
-            // Fetch orgs in background and update form when ready
-            let tx = self.task_tx.clone();
-            tokio::spawn(async move {
-                let orgs = github::get_user_orgs().await.unwrap_or_default();
-                // Send orgs as a special message - we'll parse it later
-                let _ = tx.send(TaskResult {
-                    success: true,
-                    message: format!("__ORGS__:{}", orgs.join(",")),
-                    stderr: None,
-                    operation: String::new(),
-                    invalidates_github_cache: false, // Not a real operation, just data fetch
-                }).await;
-            });
-
-            self.upload_form = Some(UploadFormState {
-                name,
-                description: String::new(),
-                private: true,
-                orgs: Vec::new(), // Will be populated when orgs arrive
-                selected_org: 0,  // 0 = personal account
-                active_field: UploadField::Name,
-                local_path: path,
+            self.set_status(format!("Reading changes in {}...", name));
+            self.load_in_background(async move {
+                let changes = git::list_changes(&path).await;
+                move |app: &mut App| {
+                    app.clear_status();
+                    if app.can_open_loaded_popup() {
+                        app.apply_file_changes(name, path, changes);
+                    }
+                }
             });
-            self.input_mode = InputMode::UploadForm;
-            self.popup = Some(Popup::new(PopupType::Upload, Vec::new()));
         }
     }
 
-    /// Submit the upload form
-    pub fn submit_upload_form(&mut self) {
-        if let Some(form) = self.upload_form.take() {
-            let org = if form.selected_org == 0 {
-                None
-            } else {
-                form.orgs.get(form.selected_org - 1).cloned()
-            };
-
-            let opts = github::CreateRepoOptions {
-                name: form.name.clone(),
-                path: form.local_path.clone(),
-                description: if form.description.is_empty() { None } else { Some(form.description) },
-                private: form.private,
-                org,
-            };
+    /// Rebuild the files popup from freshly listed changes, keeping the selection
+    fn apply_file_changes(&mut self, name: String, path: String, changes: Result<Vec<git::FileChange>>) {
+        match changes {
+            Ok(changes) => {
+                let mut content = vec![
+                    format!("Changes in {} (Space: stage/unstage, c: commit staged):", name),
+                    String::new(),
+                ];
+                if changes.is_empty() {
+                    content.push("Working tree clean".to_string());
+                }
+                content.extend(changes.iter().map(|c| format!("{}{} {}", c.index, c.worktree, c.path)));
+
+                let selected = self.popup
+                    .as_ref()
+                    .filter(|p| p.popup_type == PopupType::Files)
+                    .map(|p| p.selected)
+                    .unwrap_or(2);
+                let mut popup = Popup::new(PopupType::Files, content);
+                popup.selected = selected.min(popup.content.len().saturating_sub(1)).max(2);
+                self.files_repo = Some((name, path));
+                self.file_changes = changes;
+                self.popup = Some(popup);
+            }
+            Err(e) => {
+                self.error_log.push(ErrorLogEntry::new(format!("status {}", name), e.to_string()));
+                self.set_status_error("Failed to list changes (E: view errors)");
+            }
+        }
+    }
 
-            self.set_status(format!("Creating GitHub repo {}...", opts.name));
-            let tx = self.task_tx.clone();
-            let name = opts.name.clone();
-            let op = format!("create repo {}", name);
-            tokio::spawn(async move {
-                let result = github::create_repo(&opts).await;
-                let _ = tx.send(TaskResult {
-                    success: result.success,
-                    message: if result.success {
-                        format!("Created {}", name)
-                    } else {
-                        "Create repo failed (E: view errors)".to_string()
-                    },
-                    stderr: if result.success { None } else { Some(result.stderr) },
-                    operation: op,
-                    invalidates_github_cache: true, // New repo created on GitHub
-                }).await;
+    /// Show local branches of the selected repo
+    pub fn show_branches_popup(&mut self) {
+        let info = self.get_selected_repo()
+            .filter(|r| r.has_git)
+            .and_then(|r| r.local_path.clone().map(|p| (r.name.clone(), p)));
+        if let Some((name, path)) = info {
+            self.set_status(format!("Reading branches of {}...", name));
+            self.load_in_background(async move {
+                let branches = git::list_branches(&path).await;
+                move |app: &mut App| {
+                    app.clear_status();
+                    if app.can_open_loaded_popup() {
+                        app.apply_branches(name, path, branches);
+                    }
+                }
             });
-
-            self.close_popup();
         }
     }
 
-    /// Cancel upload form
-    pub fn cancel_upload_form(&mut self) {
-        self.upload_form = None;
-        self.close_popup();
+    /// Rebuild the branches popup from freshly listed branches, keeping the selection
+    fn apply_branches(&mut self, name: String, path: String, branches: Result<Vec<git::Branch>>) {
+        match branches {
+            Ok(branches) => {
+                let mut content = vec![
+                    format!("Branches in {} (n: new, d: delete, D: force delete):", name),
+                    String::new(),
+                ];
+                content.extend(branches.iter().map(|b| {
+                    format!("{} {}", if b.is_current { "*" } else { " " }, b.name)
+                }));
+
+                let selected = self.popup
+                    .as_ref()
+                    .filter(|p| p.popup_type == PopupType::Branches)
+                    .map(|p| p.selected)
+                    .unwrap_or(2);
+                let mut popup = Popup::new(PopupType::Branches, content);
+                popup.selected = selected.min(popup.content.len().saturating_sub(1)).max(2);
+                self.branches_repo = Some((name, path));
+                self.branches = branches;
+                self.popup = Some(popup);
+            }
+            Err(e) => {
+                self.error_log.push(ErrorLogEntry::new(format!("branches {}", name), e.to_string()));
+                self.set_status_error("Failed to list branches (E: view errors)");
+            }
+        }
     }
 
-    /// Navigate to next field in upload form
-    pub fn upload_form_next_field(&mut self) {
-        if let Some(ref mut form) = self.upload_form {
-            form.active_field = form.active_field.next();
+    /// Branch selected in the branches popup, refusing the checked-out one
+    fn selected_branch_for_delete(&mut self) -> Option<(String, String, String)> {
+        let selected = self.popup
+            .as_ref()
+            .filter(|p| p.popup_type == PopupType::Branches && p.selected >= 2)
+            .map(|p| p.selected - 2); // Account for header lines
+        let branch = selected.and_then(|idx| self.branches.get(idx).cloned())?;
+        if branch.is_current {
+            self.set_status_error(format!("Can't delete {}: it is checked out", branch.name));
+            return None;
         }
+        let (name, path) = self.branches_repo.clone()?;
+        Some((name, path, branch.name))
     }
 
-    /// Navigate to previous field in upload form
-    pub fn upload_form_prev_field(&mut self) {
-        if let Some(ref mut form) = self.upload_form {
-            form.active_field = form.active_field.prev();
+    /// Delete the selected branch with `git branch -d`; git refuses unmerged branches
+    pub fn delete_selected_branch(&mut self) {
+        if let Some((name, path, branch)) = self.selected_branch_for_delete() {
+            if self.skip_for_dry_run(format!("delete branch {} in {}", branch, name)) {
+                return;
+            }
+            self.set_status(format!("Deleting branch {}...", branch));
+            self.load_in_background(async move {
+                let result = git::delete_branch(&path, &branch, false).await;
+                let branches = git::list_branches(&path).await;
+                move |app: &mut App| {
+                    if result.success {
+                        app.set_status_completed(format!("Deleted branch {}", branch));
+                        app.pending_local_refresh = true;
+                    } else {
+                        app.error_log.push(ErrorLogEntry::new(format!("delete branch {}", branch), result.stderr));
+                        app.set_status_error(format!("Couldn't delete {} (D: force, E: view errors)", branch));
+                    }
+                    // Only refresh the popup if it is still open
+                    if app.popup.as_ref().is_some_and(|p| p.popup_type == PopupType::Branches) {
+                        app.apply_branches(name, path, branches);
+                    }
+                }
+            });
         }
     }
 
-    /// Toggle private field in upload form
-    pub fn upload_form_toggle_private(&mut self) {
-        if let Some(ref mut form) = self.upload_form {
-            form.private = !form.private;
+    /// Ask before force-deleting the selected branch, which can drop unmerged commits
+    pub fn start_force_delete_branch(&mut self) {
+        if let Some((name, path, branch)) = self.selected_branch_for_delete() {
+            let content = vec![
+                format!("Force delete branch {} in {}?", branch, name),
+                String::new(),
+                "Commits not merged anywhere else will be lost.".to_string(),
+                String::new(),
+                "Press y or Enter to delete, Esc to cancel".to_string(),
+            ];
+            self.open_confirm(ConfirmAction::ForceDeleteBranch { name, path, branch }, content);
         }
     }
 
-    /// Cycle org selection in upload form
-    pub fn upload_form_next_org(&mut self) {
-        if let Some(ref mut form) = self.upload_form {
-            let max = form.orgs.len(); // 0 is personal, then orgs
-            form.selected_org = (form.selected_org + 1) % (max + 1);
+    fn force_delete_branch(&mut self, name: String, path: String, branch: String) {
+        if self.skip_for_dry_run(format!("force delete branch {} in {}", branch, name)) {
+            return;
         }
+        self.set_status(format!("Deleting branch {}...", branch));
+        let tx = self.task_tx.clone();
+        let op = format!("delete branch {} in {}", branch, name);
+        tokio::spawn(async move {
+            let result = git::delete_branch(&path, &branch, true).await;
+            let _ = tx.send(TaskResult {
+                success: result.success,
+                message: if result.success {
+                    format!("Deleted branch {}", branch)
+                } else {
+                    "Delete branch failed (E: view errors)".to_string()
+                },
+                stderr: if result.success { None } else { Some(result.stderr) },
+                operation: op,
+                invalidates_github_cache: false, // Local git operation
+            }).await;
+        });
     }
 
-    pub fn upload_form_prev_org(&mut self) {
-        if let Some(ref mut form) = self.upload_form {
-            let max = form.orgs.len();
-            if form.selected_org == 0 {
-                form.selected_org = max;
-            } else {
-                form.selected_org -= 1;
+    /// Stage or unstage the file selected in the files popup
+    pub fn toggle_stage_selected_file(&mut self) {
+        let selected = self.popup
+            .as_ref()
+            .filter(|p| p.popup_type == PopupType::Files && p.selected >= 2)
+            .map(|p| p.selected - 2); // Account for header lines
+        let change = selected.and_then(|idx| self.file_changes.get(idx).cloned());
+        if let (Some(change), Some((name, path))) = (change, self.files_repo.clone()) {
+            if self.skip_for_dry_run(format!("{} {}", if change.is_staged() { "unstage" } else { "stage" }, change.path)) {
+                return;
             }
+            self.load_in_background(async move {
+                let result = if change.is_staged() {
+                    git::unstage_file(&path, &change.path).await
+                } else {
+                    git::stage_file(&path, &change.path).await
+                };
+                let changes = git::list_changes(&path).await;
+                move |app: &mut App| {
+                    if !result.success {
+                        app.error_log.push(ErrorLogEntry::new(format!("stage {}", name), result.stderr));
+                        app.set_status_error(format!("Failed to update {} (E: view errors)", change.path));
+                    }
+                    // Only refresh the popup if it is still open
+                    if app.popup.as_ref().is_some_and(|p| p.popup_type == PopupType::Files) {
+                        app.apply_file_changes(name, path, changes);
+                    }
+                }
+            });
         }
     }
 
+    /// Cancel the commit input
+    pub fn cancel_commit(&mut self) {
+        self.commit_form = None;
+        self.close_popup();
+    }
+
     pub fn handle_char(&mut self, c: char) {
         match self.input_mode {
             InputMode::ConfirmDelete => {
@@ -2021,6 +4571,18 @@ impl App {
                     }
                 }
             }
+            InputMode::Commit => {
+                if let Some(ref mut form) = self.commit_form {
+                    form.message.push(c);
+                }
+            }
+            InputMode::Note | InputMode::NewBranch => {
+                self.input_buffer.push(c);
+            }
+            InputMode::Search => {
+                self.search_query.push(c);
+                self.selected = 0;
+            }
             _ => {}
         }
     }
@@ -2039,13 +4601,25 @@ impl App {
                     }
                 }
             }
+            InputMode::Commit => {
+                if let Some(ref mut form) = self.commit_form {
+                    form.message.pop();
+                }
+            }
+            InputMode::Note | InputMode::NewBranch => {
+                self.input_buffer.pop();
+            }
+            InputMode::Search => {
+                self.search_query.pop();
+                self.selected = 0;
+            }
             _ => {}
         }
     }
 
     pub fn popup_next(&mut self) {
         if let Some(ref mut popup) = self.popup {
-            if popup.popup_type == PopupType::Ignored {
+            if matches!(popup.popup_type, PopupType::Ignored | PopupType::Files | PopupType::Branches) {
                 let max = popup.content.len().saturating_sub(1);
                 popup.selected = (popup.selected + 1).min(max);
             }
@@ -2054,7 +4628,7 @@ impl App {
 
     pub fn popup_prev(&mut self) {
         if let Some(ref mut popup) = self.popup {
-            if popup.popup_type == PopupType::Ignored {
+            if matches!(popup.popup_type, PopupType::Ignored | PopupType::Files | PopupType::Branches) {
                 popup.selected = popup.selected.saturating_sub(1).max(2); // Min 2 to skip header
             }
         }
@@ -2068,18 +4642,105 @@ fn normalize_github_url(url: &str) -> String {
         .to_lowercase()
 }
 
-fn get_ghq_path(root: &str, url: &str) -> String {
+fn get_ghq_path(root: &str, url: &str, layout: GhqLayout) -> String {
     let normalized = normalize_github_url(url);
     let path = normalized
         .trim_start_matches("https://")
         .trim_start_matches("http://");
-    format!("{}/{}", root, path)
+    // Split "host/owner/name"; anything unexpected keeps the full URL path
+    let parts: Vec<&str> = path.splitn(3, '/').collect();
+    let relative = match parts.as_slice() {
+        [host, owner, name] => layout.relative_path(host, owner, name),
+        _ => PathBuf::from(path),
+    };
+    Path::new(root).join(relative).to_string_lossy().to_string()
+}
+
+/// Human-readable byte count using binary units
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// First 8 characters of a gist id for status messages (char-safe, unlike a byte slice)
+fn short_gist_id(id: &str) -> String {
+    id.chars().take(8).collect()
+}
+
+/// Details lines describing whether recent commits were made by the configured git user
+fn format_authorship(identity: Option<&(String, String)>, authors: &[(String, String)]) -> Vec<String> {
+    let mut lines = vec!["Authorship:".to_string()];
+
+    match identity {
+        Some((name, email)) => lines.push(format!("  Git user: {} <{}>", name, email)),
+        None => lines.push("  Git user: (not configured)".to_string()),
+    }
+
+    // Email is the stable identity; fall back to name when no email is configured
+    let is_me = |(author_name, author_email): &(String, String)| match identity {
+        Some((_, email)) if !email.is_empty() => author_email.eq_ignore_ascii_case(email),
+        Some((name, _)) => author_name == name,
+        None => false,
+    };
+
+    if let Some(last) = authors.first() {
+        let who = if is_me(last) { "you" } else { "someone else" };
+        lines.push(format!("  Last commit: {} <{}> ({})", last.0, last.1, who));
+        let mine = authors.iter().filter(|a| is_me(a)).count();
+        lines.push(format!("  Your commits: {} of last {}", mine, authors.len()));
+    } else {
+        lines.push("  No commits yet".to_string());
+    }
+
+    lines
+}
+
+/// Rows for the starred view: the merged row when the repo is already listed
+/// (so local clones carry their status), otherwise a remote-only row
+fn starred_rows(starred: &[github::GitHubRepoInfo], repos: &[RepoRow]) -> Vec<RepoRow> {
+    starred
+        .iter()
+        .map(|gh| {
+            let id = normalize_github_url(&gh.url);
+            repos.iter().find(|r| r.id == id).cloned().unwrap_or_else(|| RepoRow {
+                id,
+                owner: Some(gh.owner.clone()),
+                name: gh.name.clone(),
+                github_url: Some(gh.url.clone()),
+                ssh_url: Some(gh.ssh_url.clone()),
+                is_fork: gh.is_fork,
+                is_private: gh.is_private,
+                is_archived: gh.is_archived,
+                last_commit_time: gh.pushed_at,
+                has_git: true,
+                is_watched: gh.is_watched,
+                is_starred: gh.is_starred,
+                default_branch: gh.default_branch.clone(),
+                ..Default::default()
+            })
+        })
+        .collect()
 }
 
 fn merge_repos(github_repos: Vec<github::GitHubRepoInfo>, local_repos: Vec<local::LocalRepo>) -> Vec<RepoRow> {
     let mut result: Vec<RepoRow> = Vec::new();
     let mut local_by_url: HashMap<String, local::LocalRepo> = HashMap::new();
 
+    // Owner casing by lowercase name; GitHub's casing wins over local remote URLs
+    let mut owner_casing: HashMap<String, String> = github_repos.iter()
+        .map(|r| (r.owner.to_lowercase(), r.owner.clone()))
+        .collect();
+
     // Index local repos by normalized URL
     for repo in local_repos {
         if let Some(ref url) = repo.remote_url {
@@ -2098,14 +4759,23 @@ fn merge_repos(github_repos: Vec<github::GitHubRepoInfo>, local_repos: Vec<local
                 is_private: false,
                 is_archived: false,
                 is_member: false,
+                local_canonical: Some(resolve_path(&repo.path)),
                 local_path: Some(repo.path),
                 git_status: Some(repo.status),
                 last_commit_time: repo.last_commit_time,
+                last_commit_hash: repo.last_commit_hash,
                 is_subrepo: repo.is_subrepo,
                 parent_repo: repo.parent_repo,
                 fork_ahead: None,
                 fork_behind: None,
                 has_git: repo.has_git,
+                is_empty: false,
+                is_shallow: repo.is_shallow,
+                is_watched: false,
+                is_starred: false,
+                default_branch: None,
+                parent_default_branch: None,
+                note: None,
             });
         }
     }
@@ -2133,21 +4803,36 @@ fn merge_repos(github_repos: Vec<github::GitHubRepoInfo>, local_repos: Vec<local
             is_archived: gh_repo.is_archived,
             is_member: gh_repo.is_member,
             local_path: local.as_ref().map(|l| l.path.clone()),
+            local_canonical: local.as_ref().map(|l| resolve_path(&l.path)),
             git_status: local.as_ref().map(|l| l.status.clone()),
             last_commit_time,
+            last_commit_hash: local.as_ref().and_then(|l| l.last_commit_hash.clone()),
             is_subrepo: local.as_ref().map(|l| l.is_subrepo).unwrap_or(false),
             parent_repo: local.as_ref().and_then(|l| l.parent_repo.clone()),
             fork_ahead: gh_repo.fork_ahead,
             fork_behind: gh_repo.fork_behind,
             has_git: local.as_ref().map(|l| l.has_git).unwrap_or(true),
+            is_empty: gh_repo.is_empty,
+            is_shallow: local.as_ref().map(|l| l.is_shallow).unwrap_or(false),
+            is_watched: gh_repo.is_watched,
+            is_starred: gh_repo.is_starred,
+            default_branch: gh_repo.default_branch,
+            parent_default_branch: gh_repo.parent_default_branch,
+            note: None,
         });
     }
 
     // Add any remaining local repos that weren't matched (different remote host, etc.)
-    for (_, repo) in local_by_url {
+    // Sorted by path so the first spelling of an owner unknown to GitHub is stable
+    let mut unmatched: Vec<local::LocalRepo> = local_by_url.into_values().collect();
+    unmatched.sort_by(|a, b| a.path.cmp(&b.path));
+    for repo in unmatched {
+        let owner = repo.remote_owner.map(|o| {
+            owner_casing.entry(o.to_lowercase()).or_insert(o).clone()
+        });
         result.push(RepoRow {
             id: repo.path.clone(),
-            owner: repo.remote_owner,
+            owner,
             name: repo.name.clone(),
             github_url: repo.remote_url.clone(),
             ssh_url: repo.remote_url,
@@ -2156,14 +4841,23 @@ fn merge_repos(github_repos: Vec<github::GitHubRepoInfo>, local_repos: Vec<local
             is_private: false,
             is_archived: false,
             is_member: false, // Not from our GitHub query
+            local_canonical: Some(resolve_path(&repo.path)),
             local_path: Some(repo.path),
             git_status: Some(repo.status),
             last_commit_time: repo.last_commit_time,
+            last_commit_hash: repo.last_commit_hash,
             is_subrepo: repo.is_subrepo,
             parent_repo: repo.parent_repo,
             fork_ahead: None,
             fork_behind: None,
             has_git: repo.has_git,
+            is_empty: false,
+            is_shallow: repo.is_shallow,
+            is_watched: false,
+            is_starred: false,
+            default_branch: None,
+            parent_default_branch: None,
+            note: None,
         });
     }
 
@@ -2187,28 +4881,65 @@ fn merge_repos(github_repos: Vec<github::GitHubRepoInfo>, local_repos: Vec<local
     result
 }
 
+/// Help lines whose key or description contains the query, each group under its header
+fn filter_help_lines<'a>(lines: &'a [String], query: &str) -> Vec<&'a String> {
+    let query = query.to_lowercase();
+    let mut result = Vec::new();
+    let mut header = None;
+    for line in lines {
+        let mut parts = line.split('|');
+        let key = parts.next().unwrap_or("");
+        let desc = parts.next().unwrap_or("");
+        if key == "HEADER" {
+            header = Some(line);
+        } else if !line.is_empty()
+            && (key.to_lowercase().contains(&query) || desc.to_lowercase().contains(&query))
+        {
+            if let Some(h) = header.take() {
+                result.push(h);
+            }
+            result.push(line);
+        }
+    }
+    result
+}
+
 // Help content lines - format: "KEY|DESCRIPTION|COLOR" where COLOR is optional
 // Colors: cyan, magenta, yellow, green, red, blue
 pub fn get_help_content(view_mode: &ViewMode) -> Vec<String> {
     match view_mode {
-        ViewMode::Repos => vec![
+        ViewMode::Repos | ViewMode::Starred => vec![
             "HEADER|Navigation".to_string(),
             "↑/↓/j/k|Move up/down|".to_string(),
+            "gg/Home/G/End|Jump to top/bottom|".to_string(),
             "←/→|Change sort column|".to_string(),
             "v|Reverse sort direction|".to_string(),
             ", .|Select prev/next column|".to_string(),
             "< >|Move column left/right|".to_string(),
-            "Tab|Switch to Gists view|cyan".to_string(),
+            "Tab|Switch view (Repos → Gists → Starred)|cyan".to_string(),
             "Enter|Show details|".to_string(),
+            "/|Search owner, name and path (Enter keeps, Esc clears)|".to_string(),
             "E|Show error log|yellow".to_string(),
+            "Z|Disk usage of local clones|".to_string(),
+            "e|Edit config in $EDITOR (reloads on exit)|".to_string(),
             "y|Copy popup to clipboard|".to_string(),
             "".to_string(),
             "HEADER|Git Actions".to_string(),
-            "g|Open lazygit|green".to_string(),
+            "g|Open lazygit (after a short pause for gg)|green".to_string(),
             "l|Pull (ff-only)|cyan".to_string(),
             "h|Push|magenta".to_string(),
             "s|Sync (pull+push)|".to_string(),
             "y|Quicksync (rebase+add+commit+push)|yellow".to_string(),
+            "c|Commit all changes (Tab: amend, Shift+Tab: push)|green".to_string(),
+            "C|Stage files, then commit staged|green".to_string(),
+            "H|Show recent commits|".to_string(),
+            "F|Show commits diverging from upstream (forks)|".to_string(),
+            "F then r|Rebase a diverged fork onto upstream|".to_string(),
+            "U|Unshallow (fetch full history)|".to_string(),
+            "B|Branches: n new, d delete, D force delete|".to_string(),
+            "Y|Copy menu: owner/name, URL, HTTPS/SSH clone URL, clone command, path|".to_string(),
+            "Ctrl+Y|Copy visible rows and columns as TSV|".to_string(),
+            "w|Show full local path|".to_string(),
             "r|Refresh all|".to_string(),
             "".to_string(),
             "HEADER|Batch Operations".to_string(),
@@ -2219,16 +4950,32 @@ pub fn get_help_content(view_mode: &ViewMode) -> Vec<String> {
             "HEADER|Repository".to_string(),
             "n|Clone repo (remote-only)|cyan".to_string(),
             "u|Upload local repo to GitHub|magenta".to_string(),
+            "L|Link local repo to matching GitHub origin|".to_string(),
             "o|Open in browser|".to_string(),
+            "!|Open issues in browser|".to_string(),
+            "@|Open pull requests in browser|".to_string(),
+            "W|Watch/unwatch notifications (◉)|".to_string(),
+            "S|Star/unstar (★)|".to_string(),
             "O|Open in file manager|".to_string(),
             "p|Toggle private/public|".to_string(),
             "P|Show/hide private repos|".to_string(),
-            "a|Toggle archived status|".to_string(),
+            "a|Archive, or unarchive an archived repo|".to_string(),
             "A|Show/hide archived repos|".to_string(),
-            "d|Delete local copy|red".to_string(),
+            "f|Cycle filter: dirty/ahead/behind/diverged|".to_string(),
+            "] [|Next/previous dirty, ahead or behind repo|".to_string(),
+            "R|Show only repos not cloned locally|".to_string(),
+            "t|Cycle Updated column: age / age in days / commit hash|".to_string(),
+            "T|Cycle type filter (all/forks/sources)|".to_string(),
+            "M|Show only my repos / all accessible|".to_string(),
+            "=|Auto-fit column widths to visible rows|".to_string(),
+            "d|Delete local copy (nogit folders: y/Enter)|red".to_string(),
             "D|Delete remote repo|red".to_string(),
             "z|Reorganize to ghq path|".to_string(),
+            "m|Rename local folder to GitHub name|".to_string(),
             "i|Init git (nogit) / Ignore repo|".to_string(),
+            "b|Pin/unpin repo to the top (★ before name)|".to_string(),
+            "N|Edit note (empty removes it)|".to_string(),
+            "#|Show only repos with notes|".to_string(),
             "I|Show ignored repos|".to_string(),
             "".to_string(),
             "HEADER|Type Icons".to_string(),
@@ -2246,13 +4993,23 @@ pub fn get_help_content(view_mode: &ViewMode) -> Vec<String> {
             "⇅|Diverged|red".to_string(),
             "*|Dirty (uncommitted)|yellow".to_string(),
             "?|No remote configured|blue".to_string(),
+            "∅|Empty GitHub repo (no commits yet)|".to_string(),
+            "◌|Shallow clone (U: unshallow)|".to_string(),
+            "⎇|Not on the default branch|".to_string(),
             "".to_string(),
-            "|Press ? or Esc to close|".to_string(),
+            "|Press ? or Esc to close, / to filter|".to_string(),
         ],
         ViewMode::Gists => vec![
             "HEADER|Navigation".to_string(),
             "↑/↓/j/k|Move up/down|".to_string(),
-            "Tab|Switch to Repos view|cyan".to_string(),
+            "gg/Home/G/End|Jump to top/bottom|".to_string(),
+            "←/→|Change sort column|".to_string(),
+            "v|Reverse sort direction|".to_string(),
+            ", .|Select prev/next column|".to_string(),
+            "< >|Move column left/right|".to_string(),
+            "/|Search description and files (Enter keeps, Esc clears)|".to_string(),
+            "Ctrl+Y|Copy visible rows and columns as TSV|".to_string(),
+            "Tab|Switch to Starred view|cyan".to_string(),
             "Enter|Show details|".to_string(),
             "".to_string(),
             "HEADER|Git Actions".to_string(),
@@ -2263,19 +5020,139 @@ pub fn get_help_content(view_mode: &ViewMode) -> Vec<String> {
             "".to_string(),
             "HEADER|Gist Actions".to_string(),
             "n|Clone gist locally|cyan".to_string(),
+            "o|Open in browser|".to_string(),
             "d|Delete gist from GitHub|red".to_string(),
             "".to_string(),
             "HEADER|Batch Operations".to_string(),
             "x|Mark/unmark for batch ops|magenta".to_string(),
             "X|Clear all marks|".to_string(),
             "".to_string(),
-            "|Press ? or Esc to close|".to_string(),
+            "|Press ? or Esc to close, / to filter|".to_string(),
         ],
     }
 }
 
 
 // Sorting helpers
+/// Compare two repos for the given sort column and direction
+/// Ties fall back to owner, then name, then id so equal rows keep a stable, readable order
+fn compare_repos(
+    a: &RepoRow,
+    b: &RepoRow,
+    sort_col: SortColumn,
+    ascending: bool,
+    username: &Option<String>,
+    roots: &[ScanRoot],
+    ghq: GhqCheck,
+) -> std::cmp::Ordering {
+    let cmp = match sort_col {
+        SortColumn::Origin => {
+            let a_owner = a.owner.as_deref().unwrap_or("~");
+            let b_owner = b.owner.as_deref().unwrap_or("~");
+            a_owner.to_lowercase().cmp(&b_owner.to_lowercase())
+        }
+        SortColumn::Name => {
+            a.name.to_lowercase().cmp(&b.name.to_lowercase())
+        }
+        SortColumn::Type => {
+            // Sort by: src (owned) < clone < fork < local
+            let a_type = repo_type_sort_order(a, username);
+            let b_type = repo_type_sort_order(b, username);
+            a_type.cmp(&b_type)
+        }
+        SortColumn::Status => {
+            // Sort by: dirty < diverged < ahead < behind < synced < no-local
+            let a_status = status_sort_order(a);
+            let b_status = status_sort_order(b);
+            a_status.cmp(&b_status)
+        }
+        SortColumn::LastUpdated => {
+            // Sort by time, None goes last
+            match (a.last_commit_time, b.last_commit_time) {
+                (Some(a_time), Some(b_time)) => a_time.cmp(&b_time),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+        }
+        SortColumn::Path => {
+            let a_path = a.local_path.as_deref().unwrap_or("~");
+            let b_path = b.local_path.as_deref().unwrap_or("~");
+            a_path.cmp(b_path)
+        }
+        SortColumn::Dirty => {
+            // Sort dirty repos first
+            let a_dirty = a.git_status.as_ref().map(|s| s.is_dirty()).unwrap_or(false);
+            let b_dirty = b.git_status.as_ref().map(|s| s.is_dirty()).unwrap_or(false);
+            b_dirty.cmp(&a_dirty) // Reverse so dirty comes first
+        }
+        SortColumn::Private => {
+            // Sort private repos first
+            b.is_private.cmp(&a.is_private)
+        }
+        SortColumn::Archived => {
+            // Sort archived repos first
+            b.is_archived.cmp(&a.is_archived)
+        }
+        SortColumn::Starred => {
+            // Sort starred repos first
+            b.is_starred.cmp(&a.is_starred)
+        }
+        SortColumn::Note => {
+            // Group annotated repos first, ordered by note text
+            match (&a.note, &b.note) {
+                (Some(a_note), Some(b_note)) => a_note.to_lowercase().cmp(&b_note.to_lowercase()),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+        }
+        SortColumn::Ghq => {
+            // Sort by ghq compliance: non-compliant first, then compliant, then N/A
+            let a_ghq = a.follows_ghq(roots, ghq);
+            let b_ghq = b.follows_ghq(roots, ghq);
+            match (a_ghq, b_ghq) {
+                (Some(false), Some(true)) => std::cmp::Ordering::Less,
+                (Some(true), Some(false)) => std::cmp::Ordering::Greater,
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                _ => std::cmp::Ordering::Equal,
+            }
+        }
+    };
+    // Apply ascending/descending
+    let primary = if ascending { cmp } else { cmp.reverse() };
+    primary.then_with(|| tie_break(a, b))
+}
+
+/// Order gists by the chosen column, falling back to the id so equal rows stay put
+fn compare_gists(a: &GistRow, b: &GistRow, column: GistSortColumn, ascending: bool) -> std::cmp::Ordering {
+    let ordering = match column {
+        GistSortColumn::Description => a.description.to_lowercase().cmp(&b.description.to_lowercase()),
+        GistSortColumn::Files => a.file_names.len().cmp(&b.file_names.len()),
+        GistSortColumn::Public => a.is_public.cmp(&b.is_public),
+        // Local before remote-only, dirty before clean
+        GistSortColumn::Status => (b.has_local(), b.is_dirty()).cmp(&(a.has_local(), a.is_dirty())),
+        // RFC 3339 timestamps from the API order correctly as strings
+        GistSortColumn::Updated => a.updated_at.cmp(&b.updated_at),
+    };
+    let ordering = if ascending { ordering } else { ordering.reverse() };
+    ordering.then_with(|| a.id.cmp(&b.id))
+}
+
+/// Deterministic tie-break: owner (local-only last), then name, then id
+fn tie_break(a: &RepoRow, b: &RepoRow) -> std::cmp::Ordering {
+    let owner_cmp = match (&a.owner, &b.owner) {
+        (Some(oa), Some(ob)) => oa.to_lowercase().cmp(&ob.to_lowercase()),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    };
+    owner_cmp
+        .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        .then_with(|| a.id.cmp(&b.id))
+}
+
 fn repo_type_sort_order(repo: &RepoRow, username: &Option<String>) -> u8 {
     // Subrepos are grouped separately at the end
     if repo.is_subrepo {
@@ -2317,3 +5194,306 @@ fn status_sort_order(repo: &RepoRow) -> u8 {
         None => 6, // No local
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    fn repo(owner: Option<&str>, name: &str, id: &str) -> RepoRow {
+        RepoRow {
+            id: id.to_string(),
+            owner: owner.map(|o| o.to_string()),
+            name: name.to_string(),
+            has_git: true,
+            ..Default::default()
+        }
+    }
+
+    fn sorted_ids(mut repos: Vec<RepoRow>, sort_col: SortColumn, ascending: bool) -> Vec<String> {
+        repos.sort_by(|a, b| compare_repos(a, b, sort_col, ascending, &None, &ScanRoot::resolve_all(&["/tmp".to_string()]), GhqCheck::default()));
+        repos.into_iter().map(|r| r.id).collect()
+    }
+
+    #[test]
+    fn ties_on_primary_key_order_by_owner_then_name() {
+        // All share the same (absent) update time, so the tie-break decides
+        let repos = vec![
+            repo(None, "tool", "/home/me/code/local/tool"),
+            repo(Some("zeta"), "tool", "https://github.com/zeta/tool"),
+            repo(Some("Alpha"), "tool", "https://github.com/alpha/tool"),
+            repo(Some("alpha"), "Beta", "https://github.com/alpha/beta"),
+        ];
+        let ids = sorted_ids(repos, SortColumn::LastUpdated, false);
+        assert_eq!(ids, vec![
+            "https://github.com/alpha/beta",
+            "https://github.com/alpha/tool",
+            "https://github.com/zeta/tool",
+            "/home/me/code/local/tool",
+        ]);
+    }
+
+    #[test]
+    fn tie_break_ignores_sort_direction() {
+        let repos = vec![
+            repo(Some("b"), "same", "https://github.com/b/same"),
+            repo(Some("a"), "same", "https://github.com/a/same"),
+        ];
+        let asc = sorted_ids(repos.clone(), SortColumn::Name, true);
+        let desc = sorted_ids(repos, SortColumn::Name, false);
+        assert_eq!(asc, desc);
+        assert_eq!(asc[0], "https://github.com/a/same");
+    }
+
+    #[test]
+    fn sort_is_stable_across_input_orders() {
+        let a = repo(Some("org"), "lib", "https://github.com/org/lib");
+        let b = repo(Some("org"), "lib", "/home/me/code/lib");
+        let forward = sorted_ids(vec![a.clone(), b.clone()], SortColumn::Type, true);
+        let backward = sorted_ids(vec![b, a], SortColumn::Type, true);
+        assert_eq!(forward, backward);
+    }
+
+    pub(crate) fn test_app(repos: Vec<RepoRow>) -> App {
+        let mut app = App::with_config(vec!["/tmp".to_string()], Config::default());
+        app.sort_column = SortColumn::Name;
+        app.sort_ascending = true;
+        app.repos = repos;
+        app.sort_repo_rows();
+        app
+    }
+
+    fn selected_name(app: &App) -> String {
+        app.get_selected_repo().map(|r| r.name.clone()).unwrap_or_default()
+    }
+
+    #[test]
+    fn sort_change_keeps_cursor_on_same_repo() {
+        let mut app = test_app(vec![
+            repo(Some("o"), "alpha", "1"),
+            repo(Some("o"), "beta", "2"),
+            repo(Some("o"), "gamma", "3"),
+        ]);
+        app.selected = 0;
+        assert_eq!(selected_name(&app), "alpha");
+
+        // Same as toggle_sort_direction, minus saving the config
+        app.sort_ascending = false;
+        app.sort_repos();
+        assert_eq!(selected_name(&app), "alpha");
+        assert_eq!(app.selected, 2);
+    }
+
+    #[test]
+    fn refresh_reorder_keeps_cursor_on_same_repo() {
+        let mut app = test_app(vec![
+            repo(Some("o"), "beta", "2"),
+            repo(Some("o"), "gamma", "3"),
+        ]);
+        app.selected = 1;
+        assert_eq!(selected_name(&app), "gamma");
+
+        // A new repo sorting ahead of the cursor arrives mid-session
+        app.handle_refresh_data(RefreshData {
+            github_username: None,
+            repos: vec![
+                repo(Some("o"), "gamma", "3"),
+                repo(Some("o"), "alpha", "1"),
+                repo(Some("o"), "beta", "2"),
+            ],
+            gists: Vec::new(),
+            starred: Vec::new(),
+            error: None,
+            errors: Vec::new(),
+            github_cache: None,
+        });
+        assert_eq!(selected_name(&app), "gamma");
+    }
+
+    #[test]
+    fn gg_jumps_to_top_and_lone_g_opens_lazygit() {
+        let mut tool = repo(Some("o"), "tool", "2");
+        tool.local_path = Some("/nonexistent/tool".to_string());
+        let mut app = test_app(vec![repo(Some("o"), "alpha", "1"), tool]);
+
+        app.selected = 1;
+        app.press_g();
+        assert_eq!(app.take_lone_g(), None); // Still waiting for a second g
+        app.press_g();
+        assert_eq!(app.selected, 0);
+        assert!(app.pending_g.is_none());
+
+        app.selected = 1;
+        app.pending_g = Instant::now().checked_sub(GG_TIMEOUT);
+        assert_eq!(app.take_lone_g().as_deref(), Some("/nonexistent/tool"));
+        assert!(app.pending_g.is_none());
+    }
+
+    #[test]
+    fn upload_name_follows_github_rules() {
+        let form = |name: &str| UploadFormState {
+            name: name.to_string(),
+            description: String::new(),
+            private: false,
+            orgs: Vec::new(),
+            selected_org: 0,
+            active_field: UploadField::Name,
+            local_path: "/src/tool".to_string(),
+        };
+        let cases = [
+            ("tool", None),
+            ("my-tool_2.0", None),
+            ("", Some("name is required")),
+            (".dotfiles", Some("name can't start with a dot")),
+            ("my tool", Some("only letters, digits, - _ and . are allowed")),
+            ("tööl", Some("only letters, digits, - _ and . are allowed")),
+            ("a/b", Some("only letters, digits, - _ and . are allowed")),
+        ];
+        for (name, expected) in cases {
+            assert_eq!(form(name).name_error(), expected, "{:?}", name);
+        }
+        assert_eq!(form(&"a".repeat(100)).name_error(), None);
+        assert_eq!(form(&"a".repeat(101)).name_error(), Some("name is longer than 100 characters"));
+    }
+
+    #[test]
+    fn disk_usage_measured_before_a_refresh_is_dropped() {
+        let mut app = test_app(Vec::new());
+        let generation = app.disk_usage_generation;
+        app.measuring_disk_usage = true;
+        app.handle_refresh_data(RefreshData {
+            github_username: None,
+            repos: Vec::new(),
+            gists: Vec::new(),
+            starred: Vec::new(),
+            error: None,
+            errors: Vec::new(),
+            github_cache: None,
+        });
+        let size = CloneSize { name: "tool".to_string(), path: "/src/tool".to_string(), bytes: 1 };
+        app.handle_disk_usage(generation, vec![size.clone()]);
+        assert!(app.disk_usage.is_none());
+        assert!(!app.measuring_disk_usage);
+
+        app.handle_disk_usage(app.disk_usage_generation, vec![size]);
+        assert_eq!(app.disk_usage.as_ref().map(Vec::len), Some(1));
+    }
+
+    #[test]
+    fn delete_confirm_targets_repo_selected_at_prompt_time() {
+        let mut target = repo(Some("o"), "beta", "2");
+        target.github_url = Some("https://github.com/o/beta".to_string());
+        target.is_member = true;
+        let mut app = test_app(vec![repo(Some("o"), "alpha", "1"), target]);
+
+        app.selected = 1;
+        app.start_delete_remote_confirm();
+
+        // Selection moves (e.g. a refresh) before the user confirms
+        app.selected = 0;
+        assert_eq!(app.pending_delete_repo().map(|r| r.name.as_str()), Some("beta"));
+    }
+
+    #[test]
+    fn ghq_path_keeps_spaces_and_unicode_in_root() {
+        let path = get_ghq_path("/home/jösé/my repos", "https://github.com/o/ünï-repo", GhqLayout::HostOwnerRepo);
+        assert_eq!(path, "/home/jösé/my repos/github.com/o/ünï-repo");
+
+        let path = get_ghq_path("/home/jösé/my repos/", "https://github.com/o/ünï-repo", GhqLayout::OwnerRepo);
+        assert_eq!(path, "/home/jösé/my repos/o/ünï-repo");
+    }
+
+    #[test]
+    fn follows_ghq_with_spaces_and_unicode_in_root() {
+        let mut row = repo(Some("o"), "ünï repo", "1");
+        row.local_path = Some("/nonexistent/my rëpos/github.com/o/ünï repo".to_string());
+        let check = GhqCheck::default();
+        assert_eq!(row.follows_ghq(&ScanRoot::resolve_all(&["/nonexistent/my rëpos".to_string()]), check), Some(true));
+        assert_eq!(row.follows_ghq(&ScanRoot::resolve_all(&["/nonexistent/my rëpos/".to_string()]), check), Some(true));
+        assert_eq!(row.follows_ghq(&ScanRoot::resolve_all(&["/nonexistent/other".to_string()]), check), Some(false));
+    }
+
+    #[test]
+    fn root_for_picks_the_root_holding_the_clone() {
+        let roots = ScanRoot::resolve_all(&["/nonexistent/code".to_string(), "/nonexistent/work".to_string(), "/nonexistent/work/oss".to_string()]);
+        let mut row = repo(Some("o"), "tool", "1");
+        assert_eq!(row.root_for(&roots).map(|r| r.path.as_str()), Some("/nonexistent/code")); // Remote-only: primary root
+        row.local_path = Some("/nonexistent/work/github.com/o/tool".to_string());
+        assert_eq!(row.root_for(&roots).map(|r| r.path.as_str()), Some("/nonexistent/work"));
+        row.local_path = Some("/nonexistent/work/oss/github.com/o/tool".to_string());
+        assert_eq!(row.root_for(&roots).map(|r| r.path.as_str()), Some("/nonexistent/work/oss"));
+        assert_eq!(row.follows_ghq(&roots, GhqCheck::default()), Some(true));
+        row.local_path = Some("/elsewhere/tool".to_string());
+        assert_eq!(row.follows_ghq(&roots, GhqCheck::default()), Some(false));
+    }
+
+    /// Fresh directory under the system temp dir, removed first if left over
+    pub(crate) fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ghall-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follows_ghq_through_symlinked_root() {
+        let base = scratch_dir("symlink-root");
+        let real = base.join("real");
+        std::fs::create_dir_all(real.join("github.com/o/tool")).unwrap();
+        let link = base.join("link");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        let mut row = repo(Some("o"), "tool", "1");
+        let check = GhqCheck::default();
+
+        // Scanned through the link, root given as the link (canonical paths as refresh stores them)
+        row.local_path = Some(link.join("github.com/o/tool").to_string_lossy().to_string());
+        row.local_canonical = row.local_path.as_deref().map(resolve_path);
+        assert_eq!(row.follows_ghq(&ScanRoot::resolve_all(&[link.to_string_lossy().to_string()]), check), Some(true));
+        // Scanned through the real path, root given as the link (and vice versa)
+        row.local_path = Some(real.join("github.com/o/tool").to_string_lossy().to_string());
+        row.local_canonical = row.local_path.as_deref().map(resolve_path);
+        assert_eq!(row.follows_ghq(&ScanRoot::resolve_all(&[link.to_string_lossy().to_string()]), check), Some(true));
+        row.local_path = Some(link.join("github.com/o/tool").to_string_lossy().to_string());
+        row.local_canonical = row.local_path.as_deref().map(resolve_path);
+        assert_eq!(row.follows_ghq(&ScanRoot::resolve_all(&[real.to_string_lossy().to_string()]), check), Some(true));
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn follows_ghq_when_expected_path_is_missing() {
+        let base = scratch_dir("missing-dest");
+        std::fs::create_dir_all(base.join("misc/tool")).unwrap();
+
+        let mut row = repo(Some("o"), "tool", "1");
+        let check = GhqCheck::default();
+
+        // Existing clone in the wrong place, with nothing at the ghq path
+        row.local_path = Some(base.join("misc/tool").to_string_lossy().to_string());
+        assert_eq!(row.follows_ghq(&ScanRoot::resolve_all(&[base.to_string_lossy().to_string()]), check), Some(false));
+        // A ghq path that does not exist on disk yet still matches
+        row.local_path = Some(base.join("github.com/O/Tool").to_string_lossy().to_string());
+        assert_eq!(row.follows_ghq(&ScanRoot::resolve_all(&[base.to_string_lossy().to_string()]), check), Some(true));
+        assert_eq!(row.follows_ghq(&ScanRoot::resolve_all(&[base.to_string_lossy().to_string()]), GhqCheck { strict_case: true, ..check }), Some(false));
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn strict_case_offers_to_fix_the_case() {
+        let base = scratch_dir("strict-case").canonicalize().unwrap();
+        std::fs::create_dir_all(base.join("github.com/O/Tool")).unwrap();
+        let mut row = repo(Some("o"), "tool", "1");
+        row.local_path = Some(base.join("github.com/O/Tool").to_string_lossy().to_string());
+
+        let config = Config { strict_ghq_case: true, ..Config::default() };
+        let mut app = App::with_config(vec![base.to_string_lossy().to_string()], config);
+        app.repos = vec![row];
+        app.reorganize_to_ghq();
+        let to = base.join("github.com/o/tool").to_string_lossy().to_string();
+        assert!(matches!(app.pending_confirm, Some(ConfirmAction::Reorganize { to: ref t, .. }) if *t == to));
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
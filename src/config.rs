@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// All available columns for the repos table
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -13,9 +13,11 @@ pub enum Column {
     Updated,
     Archived,
     Private,
+    Starred,
     Ghq,
     Status,
     Dirty,
+    Note,
     Path,
 }
 
@@ -29,9 +31,11 @@ impl Column {
             Column::Updated,
             Column::Archived,
             Column::Private,
+            Column::Starred,
             Column::Ghq,
             Column::Status,
             Column::Dirty,
+            Column::Note,
             Column::Path,
         ]
     }
@@ -45,9 +49,11 @@ impl Column {
             Column::Updated => "Updated",
             Column::Archived => "Arch",
             Column::Private => "Priv",
+            Column::Starred => "Star",
             Column::Ghq => "ghq?",
             Column::Status => "Status",
             Column::Dirty => "Dirty",
+            Column::Note => "Note",
             Column::Path => "Path",
         }
     }
@@ -61,26 +67,200 @@ impl Column {
             Column::Updated => 16,     // [Updated ▲]
             Column::Archived => 10,    // [Arch ▲]
             Column::Private => 10,     // [Priv ▲]
+            Column::Starred => 10,     // [Star ▲]
             Column::Ghq => 10,         // [ghq? ▲]
             Column::Status => 14,      // [Status ▲]
             Column::Dirty => 11,       // [Dirty ▲]
+            Column::Note => 24,        // Free text, truncated
             Column::Path => 0,         // Min constraint, takes remainder
         }
     }
 
 }
 
+/// All available columns for the gists table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GistColumn {
+    Description,
+    Files,
+    Public,
+    Dirty,
+    Status,
+    Updated,
+    Path,
+}
+
+impl GistColumn {
+    /// All columns in default order
+    pub fn default_order() -> Vec<GistColumn> {
+        vec![
+            GistColumn::Description,
+            GistColumn::Files,
+            GistColumn::Public,
+            GistColumn::Dirty,
+            GistColumn::Status,
+            GistColumn::Updated,
+            GistColumn::Path,
+        ]
+    }
+
+    /// Get display name for the column
+    pub fn name(&self) -> &'static str {
+        match self {
+            GistColumn::Description => "Description",
+            GistColumn::Files => "Files",
+            GistColumn::Public => "Public",
+            GistColumn::Dirty => "",
+            GistColumn::Status => "Status",
+            GistColumn::Updated => "Updated",
+            GistColumn::Path => "Path",
+        }
+    }
+
+    /// Get column width constraint (includes room for sort indicator [Name ▲])
+    pub fn width(&self) -> u16 {
+        match self {
+            GistColumn::Description => 0, // Min constraint, takes remainder
+            GistColumn::Files => 9,       // [Files ▲]
+            GistColumn::Public => 10,     // [Public ▲]
+            GistColumn::Dirty => 3,       // Unsortable, no header
+            GistColumn::Status => 12,     // [Status ▲]
+            GistColumn::Updated => 16,    // [Updated ▲]
+            GistColumn::Path => 25,       // Unsortable, truncated
+        }
+    }
+}
+
+/// Which actions ask for confirmation before running
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfirmLevel {
+    None,        // Only remote, gist and batch deletes are confirmed
+    #[default]
+    Destructive, // Deletes and other irreversible actions
+    All,         // Also visibility and archive changes
+}
+
+/// Directory layout used for clones under the scan root
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GhqLayout {
+    #[default]
+    HostOwnerRepo, // {root}/github.com/{owner}/{name} (ghq default)
+    OwnerRepo,     // {root}/{owner}/{name}
+}
+
+impl GhqLayout {
+    /// Path of a repo relative to the root, e.g. "github.com/owner/name"
+    pub fn relative_path(&self, host: &str, owner: &str, name: &str) -> PathBuf {
+        match self {
+            GhqLayout::HostOwnerRepo => Path::new(host).join(owner).join(name),
+            GhqLayout::OwnerRepo => Path::new(owner).join(name),
+        }
+    }
+}
+
+/// How local paths are checked against the ghq layout
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GhqCheck {
+    pub layout: GhqLayout,
+    pub strict_case: bool, // Paths differing only in case count as non-compliant
+}
+
+/// How discovery walks the scan roots
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    pub follow_symlinks: bool,
+    pub skip_dirs: Vec<String>, // Directory names not searched for subrepos
+}
+
+/// One age breakpoint for the Updated column color
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatedThreshold {
+    /// Ages below this many days use `color`
+    pub max_days: f64,
+    /// Color name ("green", "yellow", "red", "#ff8800", ...)
+    pub color: String,
+}
+
+/// Colors for the Updated column, the `[updated]` config section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatedConfig {
+    /// Breakpoints checked in order; the first one the age falls under wins
+    #[serde(default = "UpdatedConfig::default_thresholds")]
+    pub thresholds: Vec<UpdatedThreshold>,
+
+    /// Color for anything older than every threshold
+    #[serde(default = "UpdatedConfig::default_older")]
+    pub older: String,
+
+    /// Show ages in days/hours ("89d ago") instead of weeks/months/years
+    #[serde(default)]
+    pub fine: bool,
+}
+
+impl UpdatedConfig {
+    fn default_thresholds() -> Vec<UpdatedThreshold> {
+        vec![
+            UpdatedThreshold { max_days: 1.0, color: "green".to_string() },
+            UpdatedThreshold { max_days: 7.0, color: "yellow".to_string() },
+        ]
+    }
+
+    fn default_older() -> String {
+        "darkgray".to_string()
+    }
+
+    /// Color name for a commit this many seconds old
+    pub fn color_for_age(&self, age_secs: i64) -> &str {
+        let days = age_secs as f64 / 86400.0;
+        self.thresholds
+            .iter()
+            .find(|t| days < t.max_days)
+            .map(|t| t.color.as_str())
+            .unwrap_or(&self.older)
+    }
+}
+
+impl Default for UpdatedConfig {
+    fn default() -> Self {
+        Self {
+            thresholds: Self::default_thresholds(),
+            older: Self::default_older(),
+            fine: false,
+        }
+    }
+}
+
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Directories scanned for local repos; the first receives new clones.
+    /// Set by --path with --save-roots; empty scans ~/code.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<String>,
+
     /// IDs of ignored/hidden repositories
     #[serde(default)]
     pub ignored_repos: HashSet<String>,
 
+    /// IDs of pinned repositories, listed first within the current sort
+    #[serde(default)]
+    pub pinned: HashSet<String>,
+
+    /// Free-text notes keyed by repo ID
+    #[serde(default)]
+    pub notes: HashMap<String, String>,
+
     /// Visible columns in display order
     #[serde(default = "Column::default_order")]
     pub columns: Vec<Column>,
 
+    /// Visible gist columns in display order
+    #[serde(default = "GistColumn::default_order")]
+    pub gist_columns: Vec<GistColumn>,
+
     /// Default sort column name
     #[serde(default = "default_sort_column")]
     pub sort_column: String,
@@ -89,6 +269,14 @@ pub struct Config {
     #[serde(default)]
     pub sort_ascending: bool,
 
+    /// Column to sort gists by
+    #[serde(default = "default_sort_column")]
+    pub gist_sort_column: String,
+
+    /// Sort gists ascending
+    #[serde(default)]
+    pub gist_sort_ascending: bool,
+
     /// Show archived repos
     #[serde(default = "default_true")]
     pub show_archived: bool,
@@ -96,8 +284,70 @@ pub struct Config {
     /// Show private repos
     #[serde(default = "default_true")]
     pub show_private: bool,
+
+    /// Only show repos owned by the signed-in user
+    #[serde(default)]
+    pub only_mine: bool,
+
+    /// Which actions require confirmation: "none", "destructive" or "all"
+    #[serde(default)]
+    pub confirm_level: ConfirmLevel,
+
+    /// Updated column color breakpoints
+    #[serde(default)]
+    pub updated: UpdatedConfig,
+
+    /// Clone layout: "host_owner_repo" (ghq) or "owner_repo"
+    #[serde(default)]
+    pub ghq_layout: GhqLayout,
+
+    /// Flag ghq paths that differ only in letter case (for case-insensitive filesystems)
+    #[serde(default)]
+    pub strict_ghq_case: bool,
+
+    /// Follow symlinked directories while scanning for repos
+    #[serde(default = "default_true")]
+    pub follow_symlinks: bool,
+
+    /// Directory names inside a repo not searched for subrepos (dependency and build output)
+    #[serde(default = "default_skip_dirs")]
+    pub skip_dirs: Vec<String>,
+
+    /// Fetch every local repo in the background after the first load
+    #[serde(default)]
+    pub fetch_on_startup: bool,
+
+    /// Refresh local status after returning from lazygit, $EDITOR or another suspended program
+    #[serde(default = "default_true")]
+    pub refresh_on_resume: bool,
+
+    /// Ask before quitting while any local repo has uncommitted changes
+    #[serde(default)]
+    pub warn_dirty_on_quit: bool,
+
+    /// Extra clone attempts after a transient network failure
+    #[serde(default = "default_clone_retries")]
+    pub clone_retries: u32,
+
+    /// Extra `git clone` options, e.g. ["--filter=blob:none"] or ["--depth=1"]
+    /// Each entry must be a single --opt=value; the URL and path are added by ghall.
+    /// Depth-limited clones show as shallow (◌) and can be completed with U;
+    /// partial clones (--filter) are not shallow and fetch missing blobs on demand.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clone_args: Vec<String>,
+
+    /// Upload form starts with "private" checked
+    #[serde(default = "default_true")]
+    pub default_upload_private: bool,
+
+    /// Org last chosen in the upload form (unset = personal account)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_upload_owner: Option<String>,
 }
 
+/// Scan root when neither --path nor the config names one
+const DEFAULT_ROOT: &str = "~/code";
+
 fn default_sort_column() -> String {
     "updated".to_string()
 }
@@ -106,20 +356,80 @@ fn default_true() -> bool {
     true
 }
 
+fn default_skip_dirs() -> Vec<String> {
+    ["node_modules", "target", "vendor", "dist", "build"].map(String::from).to_vec()
+}
+
+fn default_clone_retries() -> u32 {
+    2
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
+            roots: Vec::new(),
             ignored_repos: HashSet::new(),
+            pinned: HashSet::new(),
+            notes: HashMap::new(),
             columns: Column::default_order(),
+            gist_columns: GistColumn::default_order(),
             sort_column: default_sort_column(),
             sort_ascending: false,
+            gist_sort_column: default_sort_column(),
+            gist_sort_ascending: false,
             show_archived: true,
             show_private: true,
+            only_mine: false,
+            confirm_level: ConfirmLevel::default(),
+            updated: UpdatedConfig::default(),
+            ghq_layout: GhqLayout::default(),
+            strict_ghq_case: false,
+            follow_symlinks: true,
+            skip_dirs: default_skip_dirs(),
+            fetch_on_startup: false,
+            refresh_on_resume: true,
+            warn_dirty_on_quit: false,
+            clone_retries: default_clone_retries(),
+            clone_args: Vec::new(),
+            default_upload_private: true,
+            last_upload_owner: None,
         }
     }
 }
 
 impl Config {
+    /// Scan roots with ~ expanded: --path values (saved for next time with --save-roots), else the saved list
+    pub fn resolve_roots(&mut self, cli_paths: Vec<String>, save: bool) -> Vec<String> {
+        if save && !cli_paths.is_empty() && cli_paths != self.roots {
+            self.roots = cli_paths.clone();
+            self.save();
+        }
+        let roots = if !cli_paths.is_empty() {
+            cli_paths
+        } else if self.roots.is_empty() {
+            vec![DEFAULT_ROOT.to_string()]
+        } else {
+            self.roots.clone()
+        };
+        roots.iter().map(|p| shellexpand::tilde(p).to_string()).collect()
+    }
+
+    /// Layout and case rules for ghq compliance checks
+    pub fn ghq_check(&self) -> GhqCheck {
+        GhqCheck {
+            layout: self.ghq_layout,
+            strict_case: self.strict_ghq_case,
+        }
+    }
+
+    /// Walk settings for local repo discovery
+    pub fn scan_options(&self) -> ScanOptions {
+        ScanOptions {
+            follow_symlinks: self.follow_symlinks,
+            skip_dirs: self.skip_dirs.clone(),
+        }
+    }
+
     /// Get the config directory path
     pub fn config_dir() -> PathBuf {
         dirs::config_dir()
@@ -132,6 +442,16 @@ impl Config {
         Self::config_dir().join("config.toml")
     }
 
+    /// Re-read the config file, reporting parse errors instead of falling back to defaults
+    pub fn reload() -> Result<Self, String> {
+        let path = Self::config_path();
+        match fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).map_err(|e| e.to_string()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
     /// Load config from file, falling back to defaults
     pub fn load() -> Self {
         let path = Self::config_path();
@@ -173,19 +493,32 @@ impl Config {
 
     /// Move selected column left
     pub fn move_column_left(&mut self, col: Column) {
-        if let Some(idx) = self.columns.iter().position(|&c| c == col) {
-            if idx > 0 {
-                self.columns.swap(idx, idx - 1);
-            }
-        }
+        swap_column(&mut self.columns, col, false);
     }
 
     /// Move selected column right
     pub fn move_column_right(&mut self, col: Column) {
-        if let Some(idx) = self.columns.iter().position(|&c| c == col) {
-            if idx < self.columns.len() - 1 {
-                self.columns.swap(idx, idx + 1);
-            }
+        swap_column(&mut self.columns, col, true);
+    }
+
+    /// Move selected gist column left
+    pub fn move_gist_column_left(&mut self, col: GistColumn) {
+        swap_column(&mut self.gist_columns, col, false);
+    }
+
+    /// Move selected gist column right
+    pub fn move_gist_column_right(&mut self, col: GistColumn) {
+        swap_column(&mut self.gist_columns, col, true);
+    }
+}
+
+/// Swap a column with its neighbour, staying put at either end
+fn swap_column<T: PartialEq>(columns: &mut [T], col: T, right: bool) {
+    if let Some(idx) = columns.iter().position(|c| *c == col) {
+        if right && idx + 1 < columns.len() {
+            columns.swap(idx, idx + 1);
+        } else if !right && idx > 0 {
+            columns.swap(idx, idx - 1);
         }
     }
 }
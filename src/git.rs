@@ -1,5 +1,7 @@
 use anyhow::Result;
 use std::path::Path;
+use std::process::Stdio;
+use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 
 /// Result of a git operation with captured output
@@ -31,6 +33,8 @@ pub struct RepoStatus {
     pub untracked: u32,
     pub staged: u32,
     pub has_remote: bool,
+    pub has_commits: bool, // False for freshly initialized repos (no HEAD yet)
+    pub default_branch: Option<String>, // From refs/remotes/origin/HEAD, when set
 }
 
 impl RepoStatus {
@@ -39,6 +43,17 @@ impl RepoStatus {
     }
 }
 
+/// Check that the git binary can be run at all
+pub async fn check_installed() -> Result<()> {
+    match Command::new("git").arg("--version").output().await {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            anyhow::bail!("git not found - install git to see local repo status")
+        }
+        Err(e) => anyhow::bail!("git could not be run: {}", e),
+    }
+}
+
 pub async fn get_repo_status(path: &str) -> Result<RepoStatus> {
     let path = Path::new(path);
 
@@ -48,9 +63,7 @@ pub async fn get_repo_status(path: &str) -> Result<RepoStatus> {
         .current_dir(path)
         .output()
         .await?;
-    let branch = String::from_utf8_lossy(&branch_output.stdout)
-        .trim()
-        .to_string();
+    let branch = clean_output(&branch_output.stdout);
 
     // Check if there are any remotes
     let remotes_output = Command::new("git")
@@ -69,23 +82,66 @@ pub async fn get_repo_status(path: &str) -> Result<RepoStatus> {
         .await?;
     let has_upstream = upstream_output.status.success();
 
+    // Check whether HEAD points at a commit (false for freshly initialized repos)
+    let head_output = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", "HEAD"])
+        .current_dir(path)
+        .output()
+        .await?;
+    let has_commits = head_output.status.success();
+
     let branch_name = if branch.is_empty() {
         "HEAD".to_string()
     } else {
         branch.clone()
     };
 
+    // Default branch as recorded by clone or `git remote set-head`
+    let default_branch = if has_any_remote {
+        let origin_head = Command::new("git")
+            .args(["symbolic-ref", "--quiet", "--short", "refs/remotes/origin/HEAD"])
+            .current_dir(path)
+            .output()
+            .await?;
+        clean_output(&origin_head.stdout)
+            .strip_prefix("origin/")
+            .filter(|b| !b.is_empty())
+            .map(str::to_string)
+    } else {
+        None
+    };
+
     let mut status = RepoStatus {
         branch: branch_name.clone(),
         has_remote: has_any_remote,
+        has_commits,
+        default_branch,
         ..Default::default()
     };
 
-    // Try to get ahead/behind counts
+    // Compare against the upstream, else origin/<branch>, else origin's default branch
+    let mut compare_ref = None;
     if has_upstream {
-        // Use configured upstream
+        compare_ref = Some("@{upstream}".to_string());
+    } else if has_any_remote {
+        let candidates = [Some(branch.as_str()).filter(|b| !b.is_empty()), status.default_branch.as_deref()];
+        for candidate in candidates.into_iter().flatten() {
+            let ref_check = Command::new("git")
+                .args(["rev-parse", "--verify", "--quiet", &format!("origin/{}", candidate)])
+                .current_dir(path)
+                .output()
+                .await?;
+            if ref_check.status.success() {
+                compare_ref = Some(format!("origin/{}", candidate));
+                break;
+            }
+        }
+    }
+
+    // Try to get ahead/behind counts
+    if let Some(compare_ref) = compare_ref {
         let rev_list = Command::new("git")
-            .args(["rev-list", "--left-right", "--count", "HEAD...@{upstream}"])
+            .args(["rev-list", "--left-right", "--count", &format!("HEAD...{}", compare_ref)])
             .current_dir(path)
             .output()
             .await?;
@@ -98,35 +154,6 @@ pub async fn get_repo_status(path: &str) -> Result<RepoStatus> {
                 status.behind = parts[1].parse().unwrap_or(0);
             }
         }
-    } else if has_any_remote && !branch.is_empty() {
-        // Fallback: try origin/<branch> if it exists
-        let ref_check = Command::new("git")
-            .args(["rev-parse", "--verify", &format!("origin/{}", branch)])
-            .current_dir(path)
-            .output()
-            .await?;
-
-        if ref_check.status.success() {
-            let rev_list = Command::new("git")
-                .args([
-                    "rev-list",
-                    "--left-right",
-                    "--count",
-                    &format!("HEAD...origin/{}", branch),
-                ])
-                .current_dir(path)
-                .output()
-                .await?;
-
-            if rev_list.status.success() {
-                let counts = String::from_utf8_lossy(&rev_list.stdout);
-                let parts: Vec<&str> = counts.trim().split('\t').collect();
-                if parts.len() == 2 {
-                    status.ahead = parts[0].parse().unwrap_or(0);
-                    status.behind = parts[1].parse().unwrap_or(0);
-                }
-            }
-        }
     }
 
     // Get working tree status
@@ -137,27 +164,37 @@ pub async fn get_repo_status(path: &str) -> Result<RepoStatus> {
         .await?;
 
     if status_output.status.success() {
-        let status_text = String::from_utf8_lossy(&status_output.stdout);
-        for line in status_text.lines() {
-            if line.len() >= 2 {
-                let index = line.chars().next().unwrap_or(' ');
-                let worktree = line.chars().nth(1).unwrap_or(' ');
-
-                if index == '?' {
-                    status.untracked += 1;
-                } else {
-                    if index != ' ' {
-                        status.staged += 1;
-                    }
-                    if worktree != ' ' {
-                        status.dirty = true;
-                    }
+        count_porcelain(&mut status, &status_output.stdout);
+    }
+
+    Ok(status)
+}
+
+/// Tally `git status --porcelain` output; works on raw bytes so odd path encodings can't shift the status columns
+fn count_porcelain(status: &mut RepoStatus, output: &[u8]) {
+    for line in output.split(|&b| b == b'\n') {
+        if let [index, worktree, ..] = line {
+            if *index == b'?' {
+                status.untracked += 1;
+            } else {
+                if *index != b' ' {
+                    status.staged += 1;
+                }
+                if *worktree != b' ' {
+                    status.dirty = true;
                 }
             }
         }
     }
+}
 
-    Ok(status)
+/// Single-line git output with invalid UTF-8 replaced and control characters dropped
+fn clean_output(output: &[u8]) -> String {
+    String::from_utf8_lossy(output)
+        .trim()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect()
 }
 
 pub async fn get_remote_url(path: &str) -> Option<String> {
@@ -169,15 +206,22 @@ pub async fn get_remote_url(path: &str) -> Option<String> {
         .ok()?;
 
     if output.status.success() {
-        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        Some(clean_output(&output.stdout))
     } else {
         None
     }
 }
 
-pub async fn fetch(path: &str) -> GitOpResult {
+pub async fn fetch(path: &str, on_progress: impl Fn(u8)) -> GitOpResult {
+    let mut cmd = Command::new("git");
+    cmd.args(["fetch", "--all", "--prune", "--progress"]).current_dir(path);
+    run_with_progress(cmd, on_progress).await
+}
+
+/// Fetch the full history of a shallow clone
+pub async fn unshallow(path: &str) -> GitOpResult {
     let output = Command::new("git")
-        .args(["fetch", "--all", "--prune"])
+        .args(["fetch", "--unshallow"])
         .env("GIT_SSH_COMMAND", SSH_COMMAND)
         .current_dir(path)
         .output()
@@ -190,19 +234,46 @@ pub async fn fetch(path: &str) -> GitOpResult {
     }
 }
 
-pub async fn pull(path: &str) -> GitOpResult {
+pub async fn pull(path: &str, on_progress: impl Fn(u8)) -> GitOpResult {
+    let mut cmd = Command::new("git");
+    cmd.args(["pull", "--ff-only", "--progress"]).current_dir(path);
+    run_with_progress(cmd, on_progress).await
+}
+
+/// Pull, also reporting how many commits came in (Some(0) when already up to date)
+/// Compares HEAD before and after rather than parsing git's localized output
+pub async fn pull_counted(path: &str, on_progress: impl Fn(u8)) -> (GitOpResult, Option<u32>) {
+    let before = head_commit(path).await;
+    let result = pull(path, on_progress).await;
+    let pulled = match (&before, result.success) {
+        (Some(before), true) => count_commits(path, &format!("{}..HEAD", before)).await,
+        _ => None,
+    };
+    (result, pulled)
+}
+
+async fn head_commit(path: &str) -> Option<String> {
     let output = Command::new("git")
-        .args(["pull", "--ff-only"])
-        .env("GIT_SSH_COMMAND", SSH_COMMAND)
+        .args(["rev-parse", "--verify", "--quiet", "HEAD"])
         .current_dir(path)
         .output()
-        .await;
+        .await
+        .ok()?;
+    let hash = clean_output(&output.stdout);
+    (output.status.success() && !hash.is_empty()).then_some(hash)
+}
 
-    match output {
-        Ok(out) if out.status.success() => GitOpResult::ok(),
-        Ok(out) => GitOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
-        Err(e) => GitOpResult::err(e.to_string()),
+async fn count_commits(path: &str, range: &str) -> Option<u32> {
+    let output = Command::new("git")
+        .args(["rev-list", "--count", range])
+        .current_dir(path)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
     }
+    clean_output(&output.stdout).parse().ok()
 }
 
 pub async fn push(path: &str) -> GitOpResult {
@@ -220,7 +291,7 @@ pub async fn push(path: &str) -> GitOpResult {
     }
 }
 
-pub async fn clone(url: &str, path: &str) -> GitOpResult {
+pub async fn clone(url: &str, path: &str, extra_args: &[String], on_progress: impl Fn(u8)) -> GitOpResult {
     // Create parent directory if needed
     if let Some(parent) = Path::new(path).parent() {
         if let Err(e) = tokio::fs::create_dir_all(parent).await {
@@ -228,9 +299,192 @@ pub async fn clone(url: &str, path: &str) -> GitOpResult {
         }
     }
 
-    let output = Command::new("git")
-        .args(["clone", url, path])
+    let mut cmd = Command::new("git");
+    cmd.args(["clone", "--progress"]).args(extra_args).args(["--", url, path]);
+    run_with_progress(cmd, on_progress).await
+}
+
+/// Run a network git command (which must pass --progress, since stderr is not
+/// a terminal), reporting the "Receiving objects" percentage as it streams.
+/// Other stderr lines are kept for the error log.
+async fn run_with_progress(mut cmd: Command, on_progress: impl Fn(u8)) -> GitOpResult {
+    let child = cmd
         .env("GIT_SSH_COMMAND", SSH_COMMAND)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(c) => c,
+        Err(e) => return GitOpResult::err(e.to_string()),
+    };
+
+    // Progress lines are separated by \r
+    let mut messages = String::new();
+    if let Some(mut stderr) = child.stderr.take() {
+        let mut buf = [0u8; 4096];
+        let mut pending: Vec<u8> = Vec::new();
+        while let Ok(n) = stderr.read(&mut buf).await {
+            if n == 0 {
+                break;
+            }
+            pending.extend_from_slice(&buf[..n]);
+            while let Some(pos) = pending.iter().position(|&b| b == b'\r' || b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=pos).collect();
+                let line = String::from_utf8_lossy(&line);
+                let line = line.trim_end();
+                match progress_percent(line) {
+                    Some(("Receiving objects", pct)) => on_progress(pct),
+                    Some(_) => {}
+                    None if !line.is_empty() => {
+                        messages.push_str(line);
+                        messages.push('\n');
+                    }
+                    None => {}
+                }
+            }
+        }
+        messages.push_str(&String::from_utf8_lossy(&pending));
+    }
+
+    match child.wait().await {
+        Ok(status) if status.success() => GitOpResult::ok(),
+        Ok(_) => GitOpResult::err(messages),
+        Err(e) => GitOpResult::err(e.to_string()),
+    }
+}
+
+/// Parse a git progress line like "Receiving objects:  43% (123/456)" into (phase, percent)
+fn progress_percent(line: &str) -> Option<(&str, u8)> {
+    let line = line.strip_prefix("remote: ").unwrap_or(line);
+    let (phase, rest) = line.split_once(':')?;
+    let (pct, _) = rest.trim_start().split_once('%')?;
+    pct.parse().ok().map(|p| (phase, p))
+}
+
+/// Clone, retrying up to `retries` more times with a short backoff when the
+/// failure looks like a network blip. Returns the result and attempts made.
+pub async fn clone_with_retry(url: &str, path: &str, extra_args: &[String], retries: u32, on_progress: impl Fn(u8)) -> (GitOpResult, u32) {
+    let mut attempt = 1;
+    loop {
+        let result = clone(url, path, extra_args, &on_progress).await;
+        if result.success || attempt > retries || !is_transient_error(&result.stderr) {
+            return (result, attempt);
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(2 * attempt as u64)).await;
+        attempt += 1;
+    }
+}
+
+/// Whether git stderr describes a network failure worth retrying (not auth or missing repos)
+fn is_transient_error(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    let permanent = [
+        "authentication failed",
+        "permission denied",
+        "repository not found",
+        "not found",
+        "already exists and is not an empty directory",
+    ];
+    if permanent.iter().any(|p| stderr.contains(p)) {
+        return false;
+    }
+
+    let transient = [
+        "connection reset",
+        "connection refused",
+        "connection timed out",
+        "timed out",
+        "could not resolve host",
+        "temporary failure in name resolution",
+        "network is unreachable",
+        "early eof",
+        "the remote end hung up unexpectedly",
+        "rpc failed",
+    ];
+    transient.iter().any(|t| stderr.contains(t))
+}
+
+/// Point `origin` at the given URL (adding it if missing), fetch it,
+/// and set the current branch's upstream when origin has a matching branch
+pub async fn set_origin(path: &str, url: &str) -> GitOpResult {
+    let has_origin = get_remote_url(path).await.is_some();
+    let args = if has_origin {
+        ["remote", "set-url", "origin", url]
+    } else {
+        ["remote", "add", "origin", url]
+    };
+
+    let remote = Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .output()
+        .await;
+
+    match remote {
+        Ok(out) if !out.status.success() => {
+            return GitOpResult::err(format!("Remote setup failed: {}", String::from_utf8_lossy(&out.stderr)));
+        }
+        Err(e) => return GitOpResult::err(format!("Remote setup failed: {}", e)),
+        _ => {}
+    }
+
+    let fetch = Command::new("git")
+        .args(["fetch", "origin"])
+        .env("GIT_SSH_COMMAND", SSH_COMMAND)
+        .current_dir(path)
+        .output()
+        .await;
+
+    match fetch {
+        Ok(out) if !out.status.success() => {
+            return GitOpResult::err(format!("Fetch failed: {}", String::from_utf8_lossy(&out.stderr)));
+        }
+        Err(e) => return GitOpResult::err(format!("Fetch failed: {}", e)),
+        _ => {}
+    }
+
+    // Track origin/<branch> if it exists; a branch only present locally has nothing to track yet
+    let branch = Command::new("git")
+        .args(["symbolic-ref", "--short", "HEAD"])
+        .current_dir(path)
+        .output()
+        .await
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default();
+
+    if !branch.is_empty() {
+        let upstream = format!("origin/{}", branch);
+        let exists = Command::new("git")
+            .args(["rev-parse", "--verify", "--quiet", &upstream])
+            .current_dir(path)
+            .output()
+            .await
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+
+        if exists {
+            let output = Command::new("git")
+                .args(["branch", "--set-upstream-to", &upstream])
+                .current_dir(path)
+                .output()
+                .await;
+
+            return match output {
+                Ok(out) if out.status.success() => GitOpResult::ok(),
+                Ok(out) => GitOpResult::err(format!("Set upstream failed: {}", String::from_utf8_lossy(&out.stderr))),
+                Err(e) => GitOpResult::err(format!("Set upstream failed: {}", e)),
+            };
+        }
+    }
+
+    GitOpResult::ok()
+}
+
+/// Initialize a git repository in the given directory
+pub async fn init(path: &str) -> GitOpResult {
+    let output = Command::new("git")
+        .args(["init"])
+        .current_dir(path)
         .output()
         .await;
 
@@ -241,10 +495,217 @@ pub async fn clone(url: &str, path: &str) -> GitOpResult {
     }
 }
 
-/// Initialize a git repository in the given directory
-pub async fn init(path: &str) -> GitOpResult {
+/// Why configured clone args would be rejected; ghall supplies the URL and path itself
+pub fn clone_args_error(args: &[String]) -> Option<String> {
+    args.iter().find(|a| !a.starts_with('-') || a.as_str() == "--").map(|a| {
+        format!("'{}' is not an option (use --opt=value, not --opt value)", a)
+    })
+}
+
+/// Why a branch name would be rejected, following the `git check-ref-format` rules
+pub fn branch_name_error(name: &str) -> Option<&'static str> {
+    if name.is_empty() {
+        return Some("required");
+    }
+    if name.starts_with('-') {
+        return Some("can't start with -");
+    }
+    if name == "@" {
+        return Some("can't be @");
+    }
+    if name.chars().any(|c| c.is_control() || c.is_whitespace() || "~^:?*[\\".contains(c)) {
+        return Some("no spaces or ~^:?*[\\");
+    }
+    if name.contains("..") || name.contains("@{") {
+        return Some("no .. or @{");
+    }
+    if name.starts_with('/') || name.ends_with('/') || name.contains("//") {
+        return Some("no empty path components");
+    }
+    if name.ends_with('.') || name.split('/').any(|part| part.starts_with('.') || part.ends_with(".lock")) {
+        return Some("no parts starting with . or ending in .lock");
+    }
+    None
+}
+
+/// Create a branch from HEAD and switch to it; uncommitted changes carry over
+pub async fn create_branch(path: &str, name: &str) -> GitOpResult {
     let output = Command::new("git")
-        .args(["init"])
+        .args(["checkout", "-b", name])
+        .current_dir(path)
+        .output()
+        .await;
+
+    match output {
+        Ok(out) if out.status.success() => GitOpResult::ok(),
+        Ok(out) => GitOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
+        Err(e) => GitOpResult::err(e.to_string()),
+    }
+}
+
+/// A local branch from `git branch`
+#[derive(Debug, Clone)]
+pub struct Branch {
+    pub name: String,
+    pub is_current: bool, // Checked out in this worktree
+}
+
+/// List local branches
+pub async fn list_branches(path: &str) -> Result<Vec<Branch>> {
+    let output = Command::new("git")
+        .args(["branch", "--format=%(HEAD) %(refname:short)"])
+        .current_dir(path)
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (head, name) = line.split_at_checked(2)?;
+            Some(Branch { name: name.to_string(), is_current: head.starts_with('*') })
+        })
+        .collect())
+}
+
+/// Delete a local branch; without `force`, git refuses if it isn't merged
+pub async fn delete_branch(path: &str, name: &str, force: bool) -> GitOpResult {
+    let output = Command::new("git")
+        .args(["branch", if force { "-D" } else { "-d" }, name])
+        .current_dir(path)
+        .output()
+        .await;
+
+    match output {
+        Ok(out) if out.status.success() => GitOpResult::ok(),
+        Ok(out) => GitOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
+        Err(e) => GitOpResult::err(e.to_string()),
+    }
+}
+
+/// A changed file from `git status --porcelain`
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    pub index: char,    // Index (staged) status letter
+    pub worktree: char, // Worktree (unstaged) status letter
+    pub path: String,
+}
+
+impl FileChange {
+    pub fn is_staged(&self) -> bool {
+        self.index != ' ' && self.index != '?'
+    }
+}
+
+/// List changed files (staged, unstaged and untracked)
+pub async fn list_changes(path: &str) -> Result<Vec<FileChange>> {
+    // -z keeps paths with spaces unquoted and separates renames cleanly
+    let output = Command::new("git")
+        .args(["status", "--porcelain", "-z"])
+        .current_dir(path)
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut entries = text.split('\0').filter(|e| !e.is_empty());
+    let mut changes = Vec::new();
+    while let Some(entry) = entries.next() {
+        let mut chars = entry.chars();
+        let index = chars.next().unwrap_or(' ');
+        let worktree = chars.next().unwrap_or(' ');
+        let file = entry.get(3..).unwrap_or("").to_string();
+        // Renames and copies are followed by the original path
+        if index == 'R' || index == 'C' {
+            entries.next();
+        }
+        changes.push(FileChange { index, worktree, path: file });
+    }
+
+    Ok(changes)
+}
+
+/// Stage a single file
+pub async fn stage_file(path: &str, file: &str) -> GitOpResult {
+    let output = Command::new("git")
+        .args(["add", "--", file])
+        .current_dir(path)
+        .output()
+        .await;
+
+    match output {
+        Ok(out) if out.status.success() => GitOpResult::ok(),
+        Ok(out) => GitOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
+        Err(e) => GitOpResult::err(e.to_string()),
+    }
+}
+
+/// Unstage a single file, keeping the worktree changes
+pub async fn unstage_file(path: &str, file: &str) -> GitOpResult {
+    let restore = Command::new("git")
+        .args(["restore", "--staged", "--", file])
+        .current_dir(path)
+        .output()
+        .await;
+
+    if let Ok(out) = &restore {
+        if out.status.success() {
+            return GitOpResult::ok();
+        }
+    }
+
+    // `restore --staged` needs HEAD; fall back for repos without commits
+    let output = Command::new("git")
+        .args(["rm", "--cached", "-r", "-q", "--", file])
+        .current_dir(path)
+        .output()
+        .await;
+
+    match output {
+        Ok(out) if out.status.success() => GitOpResult::ok(),
+        Ok(out) => GitOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
+        Err(e) => GitOpResult::err(e.to_string()),
+    }
+}
+
+/// Commit changes, staging everything first when `stage_all` is set
+/// With `amend`, rewrites the last commit; an empty message keeps the existing one
+pub async fn commit(path: &str, message: &str, amend: bool, stage_all: bool) -> GitOpResult {
+    if stage_all {
+        let add = Command::new("git")
+            .args(["add", "-A"])
+            .current_dir(path)
+            .output()
+            .await;
+
+        match add {
+            Ok(out) if !out.status.success() => {
+                return GitOpResult::err(format!("Add failed: {}", String::from_utf8_lossy(&out.stderr)));
+            }
+            Err(e) => return GitOpResult::err(format!("Add failed: {}", e)),
+            _ => {}
+        }
+    }
+
+    let mut args = vec!["commit"];
+    if amend {
+        args.push("--amend");
+    }
+    if amend && message.is_empty() {
+        args.push("--no-edit");
+    } else {
+        args.push("-m");
+        args.push(message);
+    }
+
+    let output = Command::new("git")
+        .args(&args)
         .current_dir(path)
         .output()
         .await;
@@ -350,21 +811,280 @@ pub async fn quicksync(path: &str) -> GitOpResult {
     }
 }
 
-/// Get the Unix timestamp of the last commit
-pub async fn get_last_commit_time(path: &str) -> Option<i64> {
+/// Whether a remote URL (HTTPS or SSH) points at the GitHub repo `owner/name`
+fn remote_points_at(url: &str, repo: &str) -> bool {
+    let url = url.trim_end_matches('/').trim_end_matches(".git").to_lowercase();
+    let repo = repo.to_lowercase();
+    url.ends_with(&format!("/{}", repo)) || url.ends_with(&format!(":{}", repo))
+}
+
+/// Rebase the current branch onto `upstream/<branch>` of the GitHub repo `parent` (owner/name),
+/// adding the upstream remote if missing. Conflicts abort the rebase and are reported by file.
+pub async fn rebase_onto_upstream(path: &str, parent: &str, branch: &str) -> GitOpResult {
+    let existing = Command::new("git")
+        .args(["remote", "get-url", "upstream"])
+        .current_dir(path)
+        .output()
+        .await
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+    if let Some(url) = existing {
+        if !remote_points_at(&url, parent) {
+            return GitOpResult::err(format!("The upstream remote points at {}, not {}", url, parent));
+        }
+    } else {
+        let upstream_url = format!("https://github.com/{}.git", parent);
+        let add = Command::new("git")
+            .args(["remote", "add", "upstream", &upstream_url])
+            .current_dir(path)
+            .output()
+            .await;
+        match add {
+            Ok(out) if !out.status.success() => {
+                return GitOpResult::err(format!("Adding upstream failed: {}", String::from_utf8_lossy(&out.stderr)));
+            }
+            Err(e) => return GitOpResult::err(format!("Adding upstream failed: {}", e)),
+            _ => {}
+        }
+    }
+
+    let fetch = Command::new("git")
+        .args(["fetch", "upstream", branch])
+        .env("GIT_SSH_COMMAND", SSH_COMMAND)
+        .current_dir(path)
+        .output()
+        .await;
+    match fetch {
+        Ok(out) if !out.status.success() => {
+            return GitOpResult::err(format!("Fetch failed: {}", String::from_utf8_lossy(&out.stderr)));
+        }
+        Err(e) => return GitOpResult::err(format!("Fetch failed: {}", e)),
+        _ => {}
+    }
+
+    let rebase = Command::new("git")
+        .args(["rebase", &format!("upstream/{}", branch)])
+        .current_dir(path)
+        .output()
+        .await;
+    match rebase {
+        Ok(out) if out.status.success() => GitOpResult::ok(),
+        Ok(out) => {
+            // Name the conflicting files before the abort resets them
+            let conflicts = Command::new("git")
+                .args(["diff", "--name-only", "--diff-filter=U"])
+                .current_dir(path)
+                .output()
+                .await
+                .map(|o| String::from_utf8_lossy(&o.stdout).lines().collect::<Vec<_>>().join(", "))
+                .unwrap_or_default();
+            let _ = Command::new("git")
+                .args(["rebase", "--abort"])
+                .current_dir(path)
+                .output()
+                .await;
+            if conflicts.is_empty() {
+                GitOpResult::err(format!("Rebase failed, aborted: {}", String::from_utf8_lossy(&out.stderr)))
+            } else {
+                GitOpResult::err(format!("Rebase conflicts in {}; aborted, branch unchanged", conflicts))
+            }
+        }
+        Err(e) => GitOpResult::err(format!("Rebase failed: {}", e)),
+    }
+}
+
+/// One line of `git log` output
+#[derive(Debug, Clone)]
+pub struct CommitSummary {
+    pub hash: String,
+    pub author: String,
+    pub relative_time: String,
+    pub subject: String,
+}
+
+/// Most recent `n` commits on HEAD, newest first
+pub async fn recent_commits(path: &str, n: usize) -> Result<Vec<CommitSummary>> {
+    let output = Command::new("git")
+        .args(["log", "-n", &n.to_string(), "--format=%h|%an|%ar|%s"])
+        .current_dir(path)
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let commits = stdout
+        .lines()
+        .filter_map(|line| {
+            // Subject goes last so a '|' inside it survives the split
+            let mut parts = line.splitn(4, '|');
+            Some(CommitSummary {
+                hash: parts.next()?.to_string(),
+                author: parts.next()?.to_string(),
+                relative_time: parts.next()?.to_string(),
+                subject: parts.next().unwrap_or("").to_string(),
+            })
+        })
+        .collect();
+
+    Ok(commits)
+}
+
+/// Configured git identity (name, email) for a repo, including global config
+pub async fn user_identity(path: &str) -> Option<(String, String)> {
+    let read = |key: &'static str| async move {
+        Command::new("git")
+            .args(["config", "--get", key])
+            .current_dir(path)
+            .output()
+            .await
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let name = read("user.name").await;
+    let email = read("user.email").await;
+    if name.is_empty() && email.is_empty() {
+        None
+    } else {
+        Some((name, email))
+    }
+}
+
+/// Authors (name, email) of the most recent `n` commits, newest first
+pub async fn recent_authors(path: &str, n: usize) -> Vec<(String, String)> {
+    let output = Command::new("git")
+        .args(["log", "-n", &n.to_string(), "--format=%an|%ae"])
+        .current_dir(path)
+        .output()
+        .await;
+
+    match output {
+        Ok(out) if out.status.success() => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .filter_map(|line| line.rsplit_once('|'))
+            .map(|(name, email)| (name.to_string(), email.to_string()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Get the Unix timestamp and short hash of the last commit
+pub async fn get_last_commit(path: &str) -> Option<(i64, String)> {
     let output = Command::new("git")
-        .args(["log", "-1", "--format=%ct"])
+        .args(["log", "-1", "--format=%ct %h"])
         .current_dir(path)
         .output()
         .await
         .ok()?;
 
     if output.status.success() {
-        String::from_utf8_lossy(&output.stdout)
-            .trim()
-            .parse()
-            .ok()
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (time, hash) = stdout.trim().split_once(' ')?;
+        Some((time.parse().ok()?, hash.to_string()))
     } else {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn porcelain_with_invalid_utf8_paths() {
+        let mut status = RepoStatus::default();
+        let output = b"?? caf\xe9.txt\n M \xff\xfe/readme.md\nA  new\x80file\n?? \xc3\n";
+        count_porcelain(&mut status, output);
+        assert_eq!(status.untracked, 2);
+        assert_eq!(status.staged, 1);
+        assert!(status.dirty);
+    }
+
+    #[test]
+    fn clean_output_drops_control_chars() {
+        assert_eq!(clean_output(b"main\n"), "main");
+        assert_eq!(clean_output(b"feat/\x1b[31mred\xff\r\n"), "feat/[31mred\u{fffd}");
+    }
+
+    #[test]
+    fn upstream_remote_must_match_parent() {
+        assert!(remote_points_at("https://github.com/Up/Tool.git", "up/tool"));
+        assert!(remote_points_at("git@github.com:up/tool.git", "up/tool"));
+        assert!(remote_points_at("https://github.com/up/tool/", "up/tool"));
+        assert!(!remote_points_at("https://github.com/me/tool.git", "up/tool"));
+        assert!(!remote_points_at("https://github.com/up/tool-fork.git", "up/tool"));
+    }
+
+    #[test]
+    fn only_network_failures_are_retried() {
+        let cases = [
+            ("fatal: unable to access '...': Could not resolve host: github.com", true),
+            ("ssh: connect to host github.com port 22: Connection timed out", true),
+            ("error: RPC failed; curl 56 Recv failure: Connection reset by peer", true),
+            ("fatal: the remote end hung up unexpectedly", true),
+            ("fatal: early EOF", true),
+            ("remote: Repository not found.", false),
+            ("fatal: Authentication failed for 'https://github.com/o/tool/'", false),
+            ("git@github.com: Permission denied (publickey).", false),
+            ("fatal: destination path 'tool' already exists and is not an empty directory.", false),
+            // A permanent cause wins over a transient-looking hang-up
+            ("ERROR: Repository not found.\nfatal: the remote end hung up unexpectedly", false),
+            ("", false),
+        ];
+        for (stderr, transient) in cases {
+            assert_eq!(is_transient_error(stderr), transient, "{:?}", stderr);
+        }
+    }
+
+    #[test]
+    fn branch_names_follow_check_ref_format() {
+        let cases = [
+            ("feature/login", None),
+            ("fix-123", None),
+            ("v1.2", None),
+            ("", Some("required")),
+            ("-x", Some("can't start with -")),
+            ("@", Some("can't be @")),
+            ("my branch", Some("no spaces or ~^:?*[\\")),
+            ("a~1", Some("no spaces or ~^:?*[\\")),
+            ("a:b", Some("no spaces or ~^:?*[\\")),
+            ("a..b", Some("no .. or @{")),
+            ("a@{1}", Some("no .. or @{")),
+            ("/a", Some("no empty path components")),
+            ("a/", Some("no empty path components")),
+            ("a//b", Some("no empty path components")),
+            ("a.", Some("no parts starting with . or ending in .lock")),
+            ("a/.hidden", Some("no parts starting with . or ending in .lock")),
+            ("main.lock", Some("no parts starting with . or ending in .lock")),
+        ];
+        for (name, expected) in cases {
+            assert_eq!(branch_name_error(name), expected, "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn clone_args_accept_only_options() {
+        let args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        let cases: &[(&[&str], bool)] = &[
+            (&[], true),
+            (&["--depth=1", "--single-branch"], true),
+            (&["--filter=blob:none", "-q"], true),
+            (&["--depth", "1"], false),
+            (&["--"], false),
+            (&["https://github.com/o/tool"], false),
+        ];
+        for (list, ok) in cases {
+            assert_eq!(clone_args_error(&args(list)).is_none(), *ok, "{:?}", list);
+        }
+        assert_eq!(
+            clone_args_error(&args(&["--depth", "1"])).as_deref(),
+            Some("'1' is not an option (use --opt=value, not --opt value)")
+        );
+    }
+}
@@ -3,7 +3,7 @@ use crate::git;
 use anyhow::Result;
 use chrono::DateTime;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use tokio::process::Command;
 
@@ -32,6 +32,22 @@ impl GhOpResult {
     pub fn err(stderr: String) -> Self {
         Self { success: false, stderr }
     }
+
+    /// `gh` could not be started, most often because it isn't installed
+    pub fn spawn_err(e: std::io::Error) -> Self {
+        Self::err(spawn_error_message(&e))
+    }
+}
+
+/// Shown whenever `gh` is missing from PATH
+const GH_NOT_FOUND: &str = "GitHub CLI `gh` not found - install it from cli.github.com";
+
+fn spawn_error_message(e: &std::io::Error) -> String {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        GH_NOT_FOUND.to_string()
+    } else {
+        e.to_string()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -49,6 +65,9 @@ pub struct GitHubRepoInfo {
     pub fork_behind: Option<u32>,     // Commits behind upstream (for forks)
     pub default_branch: Option<String>,        // Default branch name
     pub parent_default_branch: Option<String>, // Parent's default branch (for forks)
+    pub is_empty: bool,               // No commits yet (null defaultBranchRef)
+    pub is_watched: bool,             // Viewer is subscribed to notifications
+    pub is_starred: bool,             // Viewer has starred the repo
     pub pushed_at: Option<i64>,       // Last push timestamp (Unix)
 }
 
@@ -94,6 +113,8 @@ struct Repository {
     parent: Option<ParentRepo>,
     #[serde(rename = "defaultBranchRef")]
     default_branch_ref: Option<BranchRef>,
+    #[serde(rename = "viewerHasStarred", default)]
+    viewer_has_starred: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -153,6 +174,7 @@ query {
         isArchived
         pushedAt
         defaultBranchRef { name }
+        viewerHasStarred
         parent { nameWithOwner defaultBranchRef { name } }
       }
     }
@@ -170,6 +192,7 @@ query {
             isArchived
             pushedAt
             defaultBranchRef { name }
+            viewerHasStarred
             parent { nameWithOwner defaultBranchRef { name } }
           }
         }
@@ -223,6 +246,9 @@ pub async fn fetch_all_repos_graphql() -> Result<Vec<GitHubRepoInfo>> {
             is_member: true, // User's own repos
             fork_ahead: None,
             fork_behind: None,
+            is_empty: default_branch.is_none(),
+            is_watched: false, // Filled in from user/subscriptions
+            is_starred: repo.viewer_has_starred,
             default_branch,
             parent_default_branch,
             pushed_at,
@@ -251,6 +277,9 @@ pub async fn fetch_all_repos_graphql() -> Result<Vec<GitHubRepoInfo>> {
                 is_member: true, // User is member of org
                 fork_ahead: None,
                 fork_behind: None,
+                is_empty: default_branch.is_none(),
+                is_watched: false, // Filled in from user/subscriptions
+                is_starred: repo.viewer_has_starred,
                 default_branch,
                 parent_default_branch,
                 pushed_at,
@@ -273,23 +302,22 @@ pub async fn fetch_gists_as_rows(local_root: &str) -> Result<Vec<GistRow>> {
 
     let gists: Vec<GitHubGist> = serde_json::from_slice(&output.stdout).unwrap_or_default();
 
-    let gists_dir = format!("{}/gists", local_root);
+    let gists_dir = Path::new(local_root).join("gists");
     let mut rows = Vec::new();
 
     for g in gists {
-        let potential_path = format!("{}/{}", gists_dir, g.id);
+        let potential_path = gists_dir.join(&g.id);
         // Use symlink-following exists check
-        let local_path = if Path::new(&potential_path).exists() {
-            Some(potential_path.clone())
+        let local_path = if potential_path.exists() {
+            Some(potential_path.to_string_lossy().to_string())
         } else {
             None
         };
 
         // Get git status if local
-        let git_status = if local_path.is_some() {
-            git::get_repo_status(&potential_path).await.ok()
-        } else {
-            None
+        let git_status = match local_path {
+            Some(ref path) => git::get_repo_status(path).await.ok(),
+            None => None,
         };
 
         let description = g.description.unwrap_or_else(|| {
@@ -363,7 +391,123 @@ pub async fn create_repo(opts: &CreateRepoOptions) -> GhOpResult {
     match output {
         Ok(out) if out.status.success() => GhOpResult::ok(),
         Ok(out) => GhOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
-        Err(e) => GhOpResult::err(e.to_string()),
+        Err(e) => GhOpResult::spawn_err(e),
+    }
+}
+
+// Starred repo as flattened by the jq filter in fetch_starred_repos
+#[derive(Debug, Deserialize)]
+struct StarredRepo {
+    name: String,
+    owner: String,
+    url: String,
+    ssh_url: String,
+    private: bool,
+    fork: bool,
+    archived: bool,
+    default_branch: Option<String>,
+    pushed_at: Option<String>,
+}
+
+/// Fetch the viewer's starred repos (REST, paginated; one JSON object per line via jq)
+pub async fn fetch_starred_repos() -> Result<Vec<GitHubRepoInfo>> {
+    let output = Command::new("gh")
+        .args([
+            "api", "user/starred", "--paginate",
+            "--jq", ".[] | {name, owner: .owner.login, url: .html_url, ssh_url, private, fork, archived, default_branch, pushed_at}",
+        ])
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        anyhow::bail!("gh api user/starred failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    let repos = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<StarredRepo>(line).ok())
+        .map(|r| GitHubRepoInfo {
+            name: r.name,
+            owner: r.owner,
+            url: r.url,
+            ssh_url: r.ssh_url,
+            is_private: r.private,
+            is_fork: r.fork,
+            is_archived: r.archived,
+            fork_parent: None, // Not included in the starred listing
+            is_member: false,  // Starred, not necessarily ours
+            fork_ahead: None,
+            fork_behind: None,
+            is_empty: false,   // REST always reports a default branch name
+            is_watched: false,
+            is_starred: true,  // From the viewer's own star list
+            default_branch: r.default_branch,
+            parent_default_branch: None,
+            pushed_at: r.pushed_at.as_deref().and_then(parse_iso8601_timestamp),
+        })
+        .collect();
+
+    Ok(repos)
+}
+
+/// Full names ("owner/name", lowercased) of repos the viewer is watching
+pub async fn fetch_watched_repos() -> Result<HashSet<String>> {
+    let output = Command::new("gh")
+        .args(["api", "user/subscriptions", "--paginate", "--jq", ".[].full_name"])
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        anyhow::bail!("gh api user/subscriptions failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|s| s.trim().to_lowercase())
+        .collect())
+}
+
+/// Mark repos the viewer is watching
+pub fn apply_watched(repos: &mut [GitHubRepoInfo], watched: &HashSet<String>) {
+    for repo in repos {
+        repo.is_watched = watched.contains(&format!("{}/{}", repo.owner, repo.name).to_lowercase());
+    }
+}
+
+/// Watch (subscribe to notifications for) or unwatch a repo
+pub async fn set_subscription(repo: &str, subscribed: bool) -> GhOpResult {
+    let endpoint = format!("/repos/{}/subscription", repo);
+    let output = if subscribed {
+        Command::new("gh")
+            .args(["api", "-X", "PUT", &endpoint, "-F", "subscribed=true"])
+            .output()
+            .await
+    } else {
+        Command::new("gh")
+            .args(["api", "-X", "DELETE", &endpoint])
+            .output()
+            .await
+    };
+
+    match output {
+        Ok(out) if out.status.success() => GhOpResult::ok(),
+        Ok(out) => GhOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
+        Err(e) => GhOpResult::spawn_err(e),
+    }
+}
+
+/// Star or unstar a repo
+pub async fn set_starred(repo: &str, starred: bool) -> GhOpResult {
+    let method = if starred { "PUT" } else { "DELETE" };
+    let output = Command::new("gh")
+        .args(["api", "-X", method, &format!("/user/starred/{}", repo)])
+        .output()
+        .await;
+
+    match output {
+        Ok(out) if out.status.success() => GhOpResult::ok(),
+        Ok(out) => GhOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
+        Err(e) => GhOpResult::spawn_err(e),
     }
 }
 
@@ -395,7 +539,7 @@ pub async fn clone_gist(gist_id: &str, path: &str) -> GhOpResult {
     match output {
         Ok(out) if out.status.success() => GhOpResult::ok(),
         Ok(out) => GhOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
-        Err(e) => GhOpResult::err(e.to_string()),
+        Err(e) => GhOpResult::spawn_err(e),
     }
 }
 
@@ -408,7 +552,7 @@ pub async fn delete_gist(gist_id: &str) -> GhOpResult {
     match output {
         Ok(out) if out.status.success() => GhOpResult::ok(),
         Ok(out) => GhOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
-        Err(e) => GhOpResult::err(e.to_string()),
+        Err(e) => GhOpResult::spawn_err(e),
     }
 }
 
@@ -421,7 +565,7 @@ pub async fn delete_repo(repo: &str) -> GhOpResult {
     match output {
         Ok(out) if out.status.success() => GhOpResult::ok(),
         Ok(out) => GhOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
-        Err(e) => GhOpResult::err(e.to_string()),
+        Err(e) => GhOpResult::spawn_err(e),
     }
 }
 
@@ -434,7 +578,7 @@ pub async fn set_visibility(repo: &str, visibility: &str) -> GhOpResult {
     match output {
         Ok(out) if out.status.success() => GhOpResult::ok(),
         Ok(out) => GhOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
-        Err(e) => GhOpResult::err(e.to_string()),
+        Err(e) => GhOpResult::spawn_err(e),
     }
 }
 
@@ -448,7 +592,7 @@ pub async fn set_archived(repo: &str, archived: bool) -> GhOpResult {
         match output {
             Ok(out) if out.status.success() => GhOpResult::ok(),
             Ok(out) => GhOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
-            Err(e) => GhOpResult::err(e.to_string()),
+            Err(e) => GhOpResult::spawn_err(e),
         }
     } else {
         // Use API to unarchive (gh repo archive doesn't support --unarchive)
@@ -460,7 +604,7 @@ pub async fn set_archived(repo: &str, archived: bool) -> GhOpResult {
         match output {
             Ok(out) if out.status.success() => GhOpResult::ok(),
             Ok(out) => GhOpResult::err(String::from_utf8_lossy(&out.stderr).to_string()),
-            Err(e) => GhOpResult::err(e.to_string()),
+            Err(e) => GhOpResult::spawn_err(e),
         }
     }
 }
@@ -481,10 +625,10 @@ pub async fn get_current_user() -> Result<String> {
 /// Check if gh CLI is authenticated
 /// Returns Ok(()) if authenticated, Err with message if not
 pub async fn check_auth() -> Result<()> {
-    let output = Command::new("gh")
-        .args(["auth", "status"])
-        .output()
-        .await?;
+    let output = match Command::new("gh").args(["auth", "status"]).output().await {
+        Ok(output) => output,
+        Err(e) => anyhow::bail!(spawn_error_message(&e)),
+    };
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -504,16 +648,46 @@ struct CompareResponse {
     behind_by: u32,
 }
 
+/// A commit from the compare API
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompareCommit {
+    pub sha: String,     // Short hash
+    pub author: String,
+    pub date: String,    // YYYY-MM-DD
+    pub subject: String,
+}
+
+/// Commits reachable from `head` but not `base` (refs may be "owner:branch")
+pub async fn compare_commits(repo: &str, base: &str, head: &str) -> Result<Vec<CompareCommit>> {
+    let endpoint = format!("repos/{}/compare/{}...{}", repo, base, head);
+    let output = Command::new("gh")
+        .args([
+            "api", &endpoint,
+            "--jq", r#".commits[] | {sha: .sha[0:7], author: .commit.author.name, date: .commit.author.date[0:10], subject: (.commit.message | split("\n")[0])}"#,
+        ])
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
 /// Fetch fork comparison data for all forks in the list
 /// Updates fork_ahead and fork_behind fields in place
-pub async fn fetch_fork_comparisons(repos: &mut Vec<GitHubRepoInfo>) {
+pub async fn fetch_fork_comparisons(repos: &mut [GitHubRepoInfo]) {
     use futures::future::join_all;
 
-    // Collect indices of forks that need comparison
+    // Collect indices of forks that need comparison (empty forks have no branch to compare)
     let fork_indices: Vec<usize> = repos
         .iter()
         .enumerate()
-        .filter(|(_, r)| r.is_fork && r.fork_parent.is_some())
+        .filter(|(_, r)| r.is_fork && r.fork_parent.is_some() && !r.is_empty)
         .map(|(i, _)| i)
         .collect();
 
@@ -3,36 +3,57 @@ mod config;
 mod git;
 mod github;
 mod local;
+mod platform;
 mod ui;
 
 use anyhow::Result;
-use app::{App, DeleteType, InputMode, PopupType, ViewMode};
+use app::{App, ConfirmAction, ConfirmKind, DeleteType, InputMode, PopupType, ViewMode};
 use clap::Parser;
+use config::Config;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers, MouseEventKind},
+    event::{DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyCode, KeyEventKind, KeyModifiers, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use futures::StreamExt;
 use ratatui::prelude::*;
 use std::io;
-use std::process::Command;
+use std::process::{Command, ExitStatus};
 use std::time::Duration;
+use tokio::time::MissedTickBehavior;
 
 #[derive(Parser, Debug)]
 #[command(name = "ghall")]
 #[command(about = "A TUI for managing git repositories across GitHub and local", long_about = None)]
 struct Args {
-    /// Path to scan for local repositories
-    #[arg(short, long, default_value = "~/code")]
-    path: String,
+    /// Path to scan for local repositories; repeat for several roots (default: the saved roots, else ~/code)
+    #[arg(short, long)]
+    path: Vec<String>,
+
+    /// Save the --path roots to the config so later runs scan them without --path
+    #[arg(long, requires = "path")]
+    save_roots: bool,
+
+    /// Show what mutating actions would do without running them
+    #[arg(long, visible_alias = "read-only")]
+    dry_run: bool,
+
+    /// Print a shell script that moves non-ghq repos into place, then exit
+    #[arg(long)]
+    ghq_migration_script: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
 
-    // Expand ~ in path
-    let path = shellexpand::tilde(&args.path).to_string();
+    // Expand ~ in each root, falling back to the roots saved by an earlier --save-roots
+    let roots = Config::load().resolve_roots(args.path, args.save_roots);
+
+    if args.ghq_migration_script {
+        print!("{}", app::ghq_migration_script(&roots).await?);
+        return Ok(());
+    }
 
     // Setup terminal
     enable_raw_mode()?;
@@ -42,7 +63,8 @@ async fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Create app and run (App::new is now synchronous, refresh happens in event loop)
-    let mut app = App::new(path)?;
+    let mut app = App::new(roots)?;
+    app.dry_run = args.dry_run;
     let res = run_app(&mut terminal, &mut app).await;
 
     // Restore terminal
@@ -61,17 +83,17 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-/// Spawn lazygit in the given repo directory
-fn spawn_lazygit<B: Backend>(terminal: &mut Terminal<B>, path: &str) -> Result<()> {
+/// Run a terminal program with the TUI suspended, restoring it afterwards
+/// and queueing a local refresh so changes made meanwhile show up
+fn run_suspended<B: Backend>(terminal: &mut Terminal<B>, events: &mut EventStream, app: &mut App, cmd: &mut Command) -> Result<io::Result<ExitStatus>> {
+    // Replace the event stream so its reader thread shuts down and can't steal the child's input
+    *events = EventStream::new();
+
     // Leave TUI mode
     disable_raw_mode()?;
     execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
 
-    // Spawn lazygit
-    let status = Command::new("lazygit")
-        .arg("-p")
-        .arg(path)
-        .status();
+    let status = cmd.status();
 
     // Restore TUI mode
     enable_raw_mode()?;
@@ -80,25 +102,59 @@ fn spawn_lazygit<B: Backend>(terminal: &mut Terminal<B>, path: &str) -> Result<(
     // Force terminal clear and redraw
     terminal.clear()?;
 
-    // Check if lazygit succeeded
-    match status {
-        Ok(s) if s.success() => Ok(()),
-        Ok(_) => Ok(()), // lazygit exited with non-zero, but that's fine
-        Err(e) => Err(anyhow::anyhow!("Failed to spawn lazygit: {}", e)),
+    // Only a program that actually ran can have touched the repos
+    if status.is_ok() && app.config.refresh_on_resume {
+        app.pending_local_refresh = true;
     }
+
+    Ok(status)
 }
 
-async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
-    loop {
-        // Tick spinner for status feedback
-        app.tick_spinner();
+/// Run lazygit in the given repo directory
+fn open_lazygit<B: Backend>(terminal: &mut Terminal<B>, events: &mut EventStream, app: &mut App, path: &str) -> Result<()> {
+    match run_suspended(terminal, events, app, Command::new("lazygit").current_dir(path))? {
+        // A non-zero exit is fine; the resume refresh covers either way
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            app.set_status_error("lazygit not found on PATH - install it to use g");
+        }
+        Err(e) => app.set_status_error(format!("Failed to spawn lazygit: {}", e)),
+    }
+    Ok(())
+}
+
+/// Open config.toml in $VISUAL/$EDITOR and reload it once the editor exits
+fn edit_config<B: Backend>(terminal: &mut Terminal<B>, events: &mut EventStream, app: &mut App) -> Result<()> {
+    let path = Config::config_path();
+    if !path.exists() {
+        // Write the defaults so the editor opens a complete file
+        app.config.save();
+    }
 
-        // Check for completed background tasks
-        app.poll_tasks();
+    // The editor may carry arguments, e.g. "code --wait"
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let mut cmd = Command::new(program);
+    cmd.args(parts).arg(&path);
+
+    match run_suspended(terminal, events, app, &mut cmd)? {
+        Ok(_) => app.reload_config(),
+        Err(e) => app.set_status_error(format!("Failed to run {}: {} (config: {})", program, e, path.display())),
+    }
+    Ok(())
+}
+
+async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
+    let mut events = EventStream::new();
 
-        // Check for completed refresh data
-        app.poll_refresh();
+    // Ticks only drive the spinner and status timeout; they are skipped when idle
+    let mut ticker = tokio::time::interval(Duration::from_millis(100));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
+    loop {
         // Handle pending refresh from background tasks
         // Full refresh takes precedence over local-only refresh
         if app.pending_refresh {
@@ -112,49 +168,107 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Resul
 
         terminal.draw(|f| ui::draw(f, app))?;
 
-        // Poll for events with timeout to allow async updates
-        if event::poll(Duration::from_millis(100))? {
-            match event::read()? {
-                Event::Key(key) => {
-                    if key.kind == KeyEventKind::Press {
-                        match app.input_mode {
-                            InputMode::Normal => {
-                                if !handle_normal_mode(terminal, app, key.code, key.modifiers).await? {
-                                    return Ok(());
-                                }
-                            }
-                            InputMode::ConfirmDelete => {
-                                handle_confirm_delete_mode(app, key.code);
-                            }
-                            InputMode::UploadForm => {
-                                handle_upload_form_mode(app, key.code);
-                            }
+        // Wake on whichever comes first: terminal input, task completion, refresh data, or a tick
+        let event = tokio::select! {
+            maybe_event = events.next() => match maybe_event {
+                Some(event) => event?,
+                None => return Ok(()),
+            },
+            Some(result) = app.task_rx.recv() => {
+                app.handle_task_result(result);
+                continue;
+            }
+            Some(update) = app.update_rx.recv() => {
+                update(app);
+                continue;
+            }
+            Some(data) = app.refresh_rx.recv() => {
+                app.handle_refresh_data(data);
+                continue;
+            }
+            Some(update) = app.progress_rx.recv() => {
+                app.handle_progress(update);
+                continue;
+            }
+            Some(()) = app.config_rx.recv() => {
+                app.handle_config_changed();
+                continue;
+            }
+            Some((generation, sizes)) = app.disk_usage_rx.recv() => {
+                app.handle_disk_usage(generation, sizes);
+                continue;
+            }
+            _ = ticker.tick(), if app.needs_tick() => {
+                // Tick spinner for status feedback
+                app.tick_spinner();
+                // A lone g in the repo views opens lazygit once the gg window has passed
+                if let Some(path) = app.take_lone_g() {
+                    open_lazygit(terminal, &mut events, app, &path)?;
+                }
+                continue;
+            }
+        };
+
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                match app.input_mode {
+                    InputMode::Normal => {
+                        if !handle_normal_mode(terminal, &mut events, app, key.code, key.modifiers).await? {
+                            return Ok(());
                         }
                     }
+                    InputMode::ConfirmDelete => {
+                        handle_confirm_delete_mode(app, key.code);
+                    }
+                    InputMode::UploadForm => {
+                        handle_upload_form_mode(app, key.code);
+                    }
+                    InputMode::Commit => {
+                        handle_commit_mode(app, key.code);
+                    }
+                    InputMode::Note => {
+                        handle_note_mode(app, key.code);
+                    }
+                    InputMode::NewBranch => {
+                        handle_new_branch_mode(app, key.code);
+                    }
+                    InputMode::Search => {
+                        handle_search_mode(app, key.code);
+                    }
                 }
-                Event::Mouse(mouse) => {
-                    if app.input_mode == InputMode::Normal && app.popup.is_none() {
-                        match mouse.kind {
-                            MouseEventKind::Down(_) => {
-                                app.handle_mouse_click(mouse.row, mouse.column);
-                            }
-                            MouseEventKind::ScrollDown => {
-                                app.next();
-                            }
-                            MouseEventKind::ScrollUp => {
-                                app.previous();
-                            }
-                            _ => {}
-                        }
+            }
+            Event::Mouse(mouse) if app.input_mode == InputMode::Normal && app.popup.is_none() => {
+                match mouse.kind {
+                    MouseEventKind::Down(_) => {
+                        app.handle_mouse_click(mouse.row, mouse.column);
+                    }
+                    MouseEventKind::ScrollDown => {
+                        app.next();
+                    }
+                    MouseEventKind::ScrollUp => {
+                        app.previous();
                     }
+                    _ => {}
                 }
-                _ => {}
             }
+            Event::Mouse(mouse) if app.input_mode == InputMode::Normal => {
+                // Wheel over a popup moves list selections and scrolls text popups
+                let is_list = app.popup.as_ref()
+                    .is_some_and(|p| matches!(p.popup_type, PopupType::Ignored | PopupType::Files | PopupType::Branches));
+                match mouse.kind {
+                    MouseEventKind::ScrollDown if is_list => app.popup_next(),
+                    MouseEventKind::ScrollUp if is_list => app.popup_prev(),
+                    MouseEventKind::ScrollDown => app.scroll_down(),
+                    MouseEventKind::ScrollUp => app.scroll_up(),
+                    _ => {}
+                }
+            }
+            _ => {}
         }
     }
 }
 
-async fn handle_normal_mode<B: Backend>(terminal: &mut Terminal<B>, app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Result<bool> {
+async fn handle_normal_mode<B: Backend>(terminal: &mut Terminal<B>, events: &mut EventStream, app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> Result<bool> {
     // If popup is open, handle popup navigation
     if let Some(ref popup) = app.popup {
         match popup.popup_type {
@@ -167,6 +281,60 @@ async fn handle_normal_mode<B: Backend>(terminal: &mut Terminal<B>, app: &mut Ap
                     _ => {}
                 }
             }
+            PopupType::Files => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => app.close_popup(),
+                    KeyCode::Char('j') | KeyCode::Down => app.popup_next(),
+                    KeyCode::Char('k') | KeyCode::Up => app.popup_prev(),
+                    KeyCode::Char(' ') => app.toggle_stage_selected_file(),
+                    KeyCode::Char('c') => app.start_commit_staged(),
+                    _ => {}
+                }
+            }
+            PopupType::Branches => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('B') => app.close_popup(),
+                    KeyCode::Char('j') | KeyCode::Down => app.popup_next(),
+                    KeyCode::Char('k') | KeyCode::Up => app.popup_prev(),
+                    KeyCode::Char('n') => {
+                        app.close_popup();
+                        app.start_new_branch();
+                    }
+                    KeyCode::Char('d') => app.delete_selected_branch(),
+                    KeyCode::Char('D') => app.start_force_delete_branch(),
+                    _ => {}
+                }
+            }
+            PopupType::Confirm => {
+                match code {
+                    KeyCode::Char('y') | KeyCode::Enter => {
+                        if app.pending_confirm == Some(ConfirmAction::Quit) {
+                            return Ok(false);
+                        }
+                        app.confirm_pending_action();
+                    }
+                    KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('n') => app.close_popup(),
+                    _ => {}
+                }
+            }
+            PopupType::Copy => {
+                match code {
+                    KeyCode::Char(c @ ('n' | 'u' | 'h' | 's' | 'c' | 'p')) => app.copy_menu_entry(c),
+                    KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Y') => app.close_popup(),
+                    _ => {}
+                }
+            }
+            PopupType::Help if popup.filter.is_some() => {
+                match code {
+                    KeyCode::Esc => app.clear_popup_filter(),
+                    KeyCode::Backspace => app.pop_popup_filter(),
+                    KeyCode::Down => app.scroll_down(),
+                    KeyCode::Up => app.scroll_up(),
+                    KeyCode::Char(c) => app.push_popup_filter(c),
+                    _ => {}
+                }
+            }
+            PopupType::ForkDiff if code == KeyCode::Char('r') => app.start_rebase_fork(),
             PopupType::Details => {
                 // Details popup - Enter or Esc closes
                 match code {
@@ -182,6 +350,7 @@ async fn handle_normal_mode<B: Backend>(terminal: &mut Terminal<B>, app: &mut Ap
                     KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => app.scroll_down(),
                     KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => app.scroll_up(),
                     KeyCode::Char('y') => app.copy_popup_to_clipboard(),
+                    KeyCode::Char('/') if popup.popup_type == PopupType::Help => app.start_popup_filter(),
                     _ => {}
                 }
             }
@@ -189,15 +358,35 @@ async fn handle_normal_mode<B: Backend>(terminal: &mut Terminal<B>, app: &mut Ap
         return Ok(true);
     }
 
+    // Any other key breaks a pending gg
+    if code != KeyCode::Char('g') {
+        app.pending_g = None;
+    }
+
     // Normal navigation and commands
     match code {
+        // Esc drops an applied search before it quits
+        KeyCode::Esc if !app.search_query.is_empty() => app.clear_search(),
+
         // Quit
-        KeyCode::Esc | KeyCode::Char('q') => return Ok(false),
+        KeyCode::Esc | KeyCode::Char('q') => {
+            if app.request_quit() {
+                return Ok(false);
+            }
+        }
+
+        // Search (/)
+        KeyCode::Char('/') => app.start_search(),
 
         // Navigation
         KeyCode::Char('j') | KeyCode::Down => app.next(),
         KeyCode::Char('k') | KeyCode::Up => app.previous(),
 
+        // Jump to top (gg, Home) and bottom (G, End); in repo views g alone opens lazygit
+        KeyCode::Char('g') => app.press_g(),
+        KeyCode::Home => app.jump_to_top(),
+        KeyCode::Char('G') | KeyCode::End => app.jump_to_bottom(),
+
         // Sorting column change and direction
         KeyCode::Left => app.prev_sort_column(),
         KeyCode::Right => app.next_sort_column(),
@@ -218,6 +407,15 @@ async fn handle_normal_mode<B: Backend>(terminal: &mut Terminal<B>, app: &mut Ap
         // Error log
         KeyCode::Char('E') => app.show_error_log(),
 
+        // Copy the visible table as TSV (Ctrl+Y)
+        KeyCode::Char('y') if modifiers.contains(KeyModifiers::CONTROL) => {
+            let tsv = ui::table_tsv(app);
+            app.copy_table(tsv);
+        }
+
+        // Edit config in $EDITOR (e)
+        KeyCode::Char('e') => edit_config(terminal, events, app)?,
+
         // Refresh
         KeyCode::Char('r') => app.trigger_refresh(),
 
@@ -230,6 +428,30 @@ async fn handle_normal_mode<B: Backend>(terminal: &mut Terminal<B>, app: &mut Ap
         // Toggle show private (capital P)
         KeyCode::Char('P') => app.toggle_show_private(),
 
+        // Auto-fit column widths to the visible rows (=)
+        KeyCode::Char('=') if app.view_mode.is_repo_list() => {
+            let widths = ui::fit_column_widths(app);
+            app.set_column_widths(widths);
+        }
+
+        // Toggle remote-only filter (R)
+        KeyCode::Char('R') if app.view_mode.is_repo_list() => app.toggle_remote_only(),
+        KeyCode::Char('T') if app.view_mode.is_repo_list() => app.cycle_type_filter(),
+        KeyCode::Char('b') if app.view_mode.is_repo_list() => app.toggle_pin(),
+        KeyCode::Char('t') if app.view_mode.is_repo_list() => app.cycle_updated_display(),
+
+        // Edit the selected repo's note (N)
+        KeyCode::Char('N') if app.view_mode.is_repo_list() => app.start_note(),
+
+        // Toggle notes-only filter (#)
+        KeyCode::Char('#') if app.view_mode.is_repo_list() => app.toggle_notes_only(),
+
+        // Toggle only-my-repos filter (M)
+        KeyCode::Char('M') if app.view_mode == ViewMode::Repos => app.toggle_only_mine(),
+
+        // Cycle status filter (f)
+        KeyCode::Char('f') if app.view_mode.is_repo_list() => app.cycle_status_filter(),
+
         // Mark/unmark item for batch operations
         KeyCode::Char('x') => app.toggle_mark(),
 
@@ -239,12 +461,7 @@ async fn handle_normal_mode<B: Backend>(terminal: &mut Terminal<B>, app: &mut Ap
         // Mode-specific actions
         _ => {
             match app.view_mode {
-                ViewMode::Repos => {
-                    if let Some(lazygit_path) = handle_repos_action(app, code).await? {
-                        spawn_lazygit(terminal, &lazygit_path)?;
-                        app.trigger_refresh();
-                    }
-                }
+                ViewMode::Repos | ViewMode::Starred => handle_repos_action(app, code).await?,
                 ViewMode::Gists => handle_gists_action(app, code).await?,
             }
         }
@@ -253,8 +470,7 @@ async fn handle_normal_mode<B: Backend>(terminal: &mut Terminal<B>, app: &mut Ap
     Ok(true)
 }
 
-/// Returns Some(path) if lazygit should be opened at that path
-async fn handle_repos_action(app: &mut App, code: KeyCode) -> Result<Option<String>> {
+async fn handle_repos_action(app: &mut App, code: KeyCode) -> Result<()> {
     match code {
         // Clone remote-only repo (n for new/clone)
         KeyCode::Char('n') => {
@@ -294,10 +510,54 @@ async fn handle_repos_action(app: &mut App, code: KeyCode) -> Result<Option<Stri
             }
         }
 
-        // Open lazygit (g) - only if has local
-        KeyCode::Char('g') => {
-            if let Some(path) = app.get_selected_repo().and_then(|r| r.local_path.clone()) {
-                return Ok(Some(path));
+        // Commit all changes (c) - only for local git repos
+        KeyCode::Char('c') => {
+            let can_commit = app.get_selected_repo()
+                .map(|r| r.has_local() && r.has_git)
+                .unwrap_or(false);
+            if can_commit {
+                app.start_commit();
+            }
+        }
+
+        // Per-file staging popup (C)
+        KeyCode::Char('C') => {
+            let can_commit = app.get_selected_repo()
+                .map(|r| r.has_local() && r.has_git)
+                .unwrap_or(false);
+            if can_commit {
+                app.show_files_popup();
+            }
+        }
+
+        // Fetch full history of a shallow clone (U)
+        KeyCode::Char('U') => app.unshallow_selected(),
+
+        // Branch list: create, delete (B)
+        KeyCode::Char('B') => app.show_branches_popup(),
+
+        // Watch/unwatch GitHub notifications (W)
+        KeyCode::Char('W') => app.toggle_watch_selected(),
+
+        // Commits diverging from upstream for forks (F)
+        KeyCode::Char('F') => app.show_fork_diff(),
+
+        // Star/unstar on GitHub (S)
+        KeyCode::Char('S') => app.toggle_star_selected(),
+
+        // Full local path without widening the column (w)
+        KeyCode::Char('w') => app.show_full_path(),
+
+        // Copy menu: owner/name, URL, clone command, path (Y)
+        KeyCode::Char('Y') => app.show_copy_menu(),
+
+        // Recent commits popup (H for history)
+        KeyCode::Char('H') => {
+            let has_history = app.get_selected_repo()
+                .map(|r| r.has_local() && r.has_git)
+                .unwrap_or(false);
+            if has_history {
+                app.show_log_popup();
             }
         }
 
@@ -323,20 +583,23 @@ async fn handle_repos_action(app: &mut App, code: KeyCode) -> Result<Option<Stri
 
         // Open in browser (o)
         KeyCode::Char('o') => {
-            if let Some(url) = app.get_selected_repo().and_then(|r| r.github_url.clone()) {
-                let _ = Command::new("xdg-open")
-                    .arg(&url)
-                    .spawn();
-            }
+            app.open_in_browser();
         }
 
+        // Next/previous repo needing attention (] [)
+        KeyCode::Char(']') => app.jump_to_attention(true),
+        KeyCode::Char('[') => app.jump_to_attention(false),
+
+        // Disk usage of local clones (Z)
+        KeyCode::Char('Z') => app.show_disk_usage(),
+
+        // Open issues (!) and pull requests (@) in browser
+        KeyCode::Char('!') => app.open_repo_page("issues"),
+        KeyCode::Char('@') => app.open_repo_page("pulls"),
+
         // Open in file manager (O)
         KeyCode::Char('O') => {
-            if let Some(path) = app.get_selected_repo().and_then(|r| r.local_path.clone()) {
-                let _ = Command::new("xdg-open")
-                    .arg(&path)
-                    .spawn();
-            }
+            app.open_in_file_manager();
         }
 
         // Upload local-only repo to GitHub (u)
@@ -349,6 +612,9 @@ async fn handle_repos_action(app: &mut App, code: KeyCode) -> Result<Option<Stri
             }
         }
 
+        // Link local-only repo to a matching GitHub origin (L)
+        KeyCode::Char('L') => app.start_relink_remote(),
+
         // Delete local copy (d for delete) - only if has local
         KeyCode::Char('d') => {
             let has_local = app.get_selected_repo()
@@ -384,10 +650,13 @@ async fn handle_repos_action(app: &mut App, code: KeyCode) -> Result<Option<Stri
             }
         }
 
+        // Rename local folder to the GitHub repo name (m)
+        KeyCode::Char('m') => app.start_rename_folder(),
+
         // Reorganize to ghq path (z)
         KeyCode::Char('z') => {
             let needs_reorg = app.get_selected_repo()
-                .map(|r| r.follows_ghq(&app.local_root) == Some(false))
+                .map(|r| r.follows_ghq(&app.scan_roots, app.config.ghq_check()) == Some(false))
                 .unwrap_or(false);
             if needs_reorg {
                 app.reorganize_to_ghq();
@@ -396,7 +665,7 @@ async fn handle_repos_action(app: &mut App, code: KeyCode) -> Result<Option<Stri
 
         _ => {}
     }
-    Ok(None)
+    Ok(())
 }
 
 async fn handle_gists_action(app: &mut App, code: KeyCode) -> Result<()> {
@@ -436,31 +705,45 @@ async fn handle_gists_action(app: &mut App, code: KeyCode) -> Result<()> {
             app.start_gist_delete_confirm();
         }
 
+        // Open in browser (o)
+        KeyCode::Char('o') => {
+            app.open_in_browser();
+        }
+
         _ => {}
     }
     Ok(())
 }
 
 fn handle_confirm_delete_mode(app: &mut App, code: KeyCode) {
+    // Local and gist deletes answer with a keypress; remote and batch deletes are typed
+    let typed = app.confirm_dialog.as_ref().map(|d| d.kind == ConfirmKind::Typed).unwrap_or(true);
     match code {
         KeyCode::Esc => {
             app.pending_delete = None;
             app.close_popup();
         }
-        KeyCode::Enter => {
-            match app.pending_delete {
-                Some(DeleteType::LocalRepo) => app.delete_local_repo(),
-                Some(DeleteType::RemoteRepo) => app.delete_remote_repo(),
-                Some(DeleteType::Gist) => app.delete_gist(),
-                None => app.close_popup(),
-            }
+        KeyCode::Char('n') | KeyCode::Char('N') if !typed => {
+            app.pending_delete = None;
+            app.close_popup();
         }
-        KeyCode::Char(c) => app.handle_char(c),
+        KeyCode::Char('y') | KeyCode::Char('Y') if !typed => submit_delete(app),
+        KeyCode::Enter if typed => submit_delete(app),
+        KeyCode::Char(c) if typed => app.handle_char(c),
         KeyCode::Backspace => app.handle_backspace(),
         _ => {}
     }
 }
 
+fn submit_delete(app: &mut App) {
+    match app.pending_delete {
+        Some(DeleteType::LocalRepo) => app.delete_local_repo(),
+        Some(DeleteType::RemoteRepo) => app.delete_remote_repo(),
+        Some(DeleteType::Gist) => app.delete_gist(),
+        None => app.close_popup(),
+    }
+}
+
 fn handle_upload_form_mode(app: &mut App, code: KeyCode) {
     use app::UploadField;
 
@@ -507,3 +790,47 @@ fn handle_upload_form_mode(app: &mut App, code: KeyCode) {
         _ => {}
     }
 }
+
+fn handle_note_mode(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Esc => app.cancel_note(),
+        KeyCode::Enter => app.submit_note(),
+        KeyCode::Char(c) => app.handle_char(c),
+        KeyCode::Backspace => app.handle_backspace(),
+        _ => {}
+    }
+}
+
+fn handle_search_mode(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Esc => app.clear_search(),
+        KeyCode::Enter => app.submit_search(),
+        KeyCode::Down => app.next(),
+        KeyCode::Up => app.previous(),
+        KeyCode::Char(c) => app.handle_char(c),
+        KeyCode::Backspace => app.handle_backspace(),
+        _ => {}
+    }
+}
+
+fn handle_new_branch_mode(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Esc => app.cancel_new_branch(),
+        KeyCode::Enter => app.submit_new_branch(),
+        KeyCode::Char(c) => app.handle_char(c),
+        KeyCode::Backspace => app.handle_backspace(),
+        _ => {}
+    }
+}
+
+fn handle_commit_mode(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Esc => app.cancel_commit(),
+        KeyCode::Enter => app.submit_commit(),
+        KeyCode::Tab => app.commit_form_toggle_amend(),
+        KeyCode::BackTab => app.commit_form_toggle_push(),
+        KeyCode::Char(c) => app.handle_char(c),
+        KeyCode::Backspace => app.handle_backspace(),
+        _ => {}
+    }
+}
@@ -0,0 +1,66 @@
+use std::io::Write;
+use std::process::{Command, ExitStatus, Stdio};
+
+/// Hint shown when no clipboard command worked
+#[cfg(target_os = "macos")]
+pub const CLIPBOARD_INSTALL_HINT: &str = "Failed to copy (pbcopy unavailable)";
+#[cfg(windows)]
+pub const CLIPBOARD_INSTALL_HINT: &str = "Failed to copy to clipboard";
+#[cfg(not(any(target_os = "macos", windows)))]
+pub const CLIPBOARD_INSTALL_HINT: &str = "Failed to copy (install wl-copy or xclip)";
+
+/// Open a URL or path with the system handler (browser, Finder, file manager)
+/// The child is spawned detached so the event loop never waits on it
+pub fn open(target: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        // `start` is a cmd builtin; the empty string is the window title
+        let mut c = Command::new("cmd");
+        c.args(["/C", "start", ""]);
+        c
+    } else {
+        Command::new("xdg-open")
+    };
+
+    command
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+/// Copy text to the system clipboard
+/// macOS uses pbcopy, Windows the built-in `clip`, elsewhere wl-copy (Wayland) then xclip (X11)
+pub fn copy_to_clipboard(content: &str) -> std::io::Result<()> {
+    let result = if cfg!(target_os = "macos") {
+        pipe_to_command("pbcopy", &[], content)
+    } else if cfg!(windows) {
+        pipe_to_command("clip", &[], content)
+    } else {
+        Command::new("wl-copy")
+            .arg(content)
+            .status()
+            .or_else(|_| pipe_to_command("xclip", &["-selection", "clipboard"], content))
+    };
+
+    match result {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(std::io::Error::other(format!("clipboard command exited with {}", status))),
+        Err(e) => Err(e),
+    }
+}
+
+/// Pipe text into a command's stdin and wait for it
+fn pipe_to_command(program: &str, args: &[&str], content: &str) -> std::io::Result<ExitStatus> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(content.as_bytes())?;
+    }
+    child.wait()
+}
@@ -1,12 +1,14 @@
-use crate::app::{App, DeleteType, GistRow, InputMode, PopupType, RepoRow, SortColumn, UploadField, ViewMode};
-use crate::config::Column;
+use crate::app::{App, ConfirmDialog, ConfirmKind, GistRow, GistSortColumn, InputMode, PopupType, RepoRow, SortColumn, StatusFilter, TypeFilter, UploadField, ViewMode};
+use crate::config::{Column, GistColumn, UpdatedConfig};
+use crate::git;
+use std::collections::HashMap;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{
         Block, Borders, Cell, Clear, Paragraph, Row, Scrollbar, ScrollbarOrientation,
-        ScrollbarState, Table,
+        ScrollbarState, Table, Wrap,
     },
     Frame,
 };
@@ -27,8 +29,10 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     // Main content - table (store area for mouse detection)
     let table_area = chunks[1];
     app.table_area = Some((table_area.y, table_area.height));
+    // Borders (2) and header (1) leave the rest for rows
+    app.keep_selected_visible(table_area.height.saturating_sub(3) as usize);
     match app.view_mode {
-        ViewMode::Repos => draw_repos_table(f, table_area, app),
+        ViewMode::Repos | ViewMode::Starred => draw_repos_table(f, table_area, app),
         ViewMode::Gists => draw_gists_table(f, table_area, app),
     }
 
@@ -37,40 +41,132 @@ pub fn draw(f: &mut Frame, app: &mut App) {
 
     // Draw popups/input modes
     match app.input_mode {
-        InputMode::ConfirmDelete => draw_confirm_delete_popup(f, app),
+        InputMode::ConfirmDelete => {
+            if let Some(ref dialog) = app.confirm_dialog {
+                draw_confirm_dialog(f, dialog, &app.confirm_buffer);
+            }
+        }
         InputMode::UploadForm => draw_upload_form_popup(f, app),
+        InputMode::Commit => draw_commit_popup(f, app),
+        InputMode::Note => draw_note_popup(f, app),
+        InputMode::NewBranch => draw_new_branch_popup(f, app),
+        InputMode::Search => {} // Query is drawn in the status bar
         InputMode::Normal => {
             if let Some(ref popup) = app.popup {
-                draw_popup(f, popup);
+                match app.confirm_dialog {
+                    Some(ref dialog) if popup.popup_type == PopupType::Confirm => draw_confirm_dialog(f, dialog, ""),
+                    _ => draw_popup(f, popup),
+                }
             }
         }
     }
 }
 
 fn draw_title_bar(f: &mut Frame, area: Rect, app: &App) {
-    let (repos_style, gists_style) = match app.view_mode {
-        ViewMode::Repos => (
-            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
-            Style::default().fg(Color::DarkGray),
-        ),
-        ViewMode::Gists => (
-            Style::default().fg(Color::DarkGray),
-            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
-        ),
+    let tab_style = |mode: ViewMode| {
+        if app.view_mode == mode {
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        }
     };
 
-    let title = Line::from(vec![
+    let mut spans = vec![
         Span::styled(" ghall ", Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
         Span::raw("│ "),
-        Span::styled("Repos", repos_style),
+        Span::styled("Repos", tab_style(ViewMode::Repos)),
         Span::raw("  "),
-        Span::styled("Gists", gists_style),
+        Span::styled("Gists", tab_style(ViewMode::Gists)),
+        Span::raw("  "),
+        Span::styled("Starred", tab_style(ViewMode::Starred)),
         Span::styled("  (Tab to switch)", Style::default().fg(Color::DarkGray)),
-    ]);
+    ];
+
+    if app.dry_run {
+        spans.push(Span::raw("  │ "));
+        spans.push(Span::styled("DRY RUN", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::REVERSED)));
+    }
+
+    if app.view_mode == ViewMode::Repos {
+        spans.extend(format_status_summary(app));
+        if app.config.only_mine {
+            spans.push(Span::raw("  │ "));
+            spans.push(Span::styled("mine", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
+        }
+    }
+    if app.view_mode.is_repo_list() && app.remote_only {
+        spans.push(Span::raw("  │ "));
+        spans.push(Span::styled(
+            format!("{} not cloned", app.visible_repos().len()),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        ));
+    }
+
+    if app.view_mode.is_repo_list() && app.notes_only {
+        spans.push(Span::raw("  │ "));
+        spans.push(Span::styled(
+            format!("{} with notes", app.visible_repos().len()),
+            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        ));
+    }
+    if app.view_mode.is_repo_list() && app.type_filter != TypeFilter::All {
+        spans.push(Span::raw("  │ "));
+        spans.push(Span::styled(
+            format!("{} {}", app.visible_repos().len(), app.type_filter.label()),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        ));
+    }
+
+    if !app.search_query.is_empty() && app.input_mode != InputMode::Search {
+        spans.push(Span::raw("  │ "));
+        spans.push(Span::styled(
+            format!("/{} ({})", app.search_query, app.visible_list_len()),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        ));
+    }
+
+    let title = Line::from(spans);
 
     f.render_widget(Paragraph::new(title), area);
 }
 
+/// Compact "5 dirty · 3 ahead · 1 diverged" summary; the active filter is highlighted
+fn format_status_summary(app: &App) -> Vec<Span<'static>> {
+    let entries = [
+        (StatusFilter::Dirty, Color::Yellow),
+        (StatusFilter::Ahead, Color::Green),
+        (StatusFilter::Behind, Color::Red),
+        (StatusFilter::Diverged, Color::Magenta),
+    ];
+
+    let mut spans = vec![Span::raw("  │ ")];
+    let mut first = true;
+    for (filter, color) in entries {
+        let count = app.status_summary.count(filter);
+        if count == 0 && app.status_filter != filter {
+            continue;
+        }
+        if !first {
+            spans.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
+        }
+        first = false;
+
+        let mut style = Style::default().fg(color);
+        if app.status_filter == filter {
+            style = style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+        }
+        spans.push(Span::styled(format!("{} {}", count, filter.label()), style));
+    }
+
+    if first {
+        spans.push(Span::styled("all clean", Style::default().fg(Color::DarkGray)));
+    }
+    if app.status_filter == StatusFilter::All {
+        spans.push(Span::styled("  (f to filter)", Style::default().fg(Color::DarkGray)));
+    }
+    spans
+}
+
 fn draw_repos_table(f: &mut Frame, area: Rect, app: &App) {
     let block = Block::default()
         .borders(Borders::ALL)
@@ -93,7 +189,7 @@ fn draw_repos_table(f: &mut Frame, area: Rect, app: &App) {
 
     // Build widths dynamically based on visible columns
     let widths: Vec<Constraint> = columns.iter().map(|col| {
-        let w = col.width();
+        let w = app.column_width(*col);
         if w == 0 {
             Constraint::Min(20) // Path column takes remainder
         } else {
@@ -117,10 +213,13 @@ fn draw_repos_table(f: &mut Frame, area: Rect, app: &App) {
         .style(Style::default().fg(Color::Cyan))
         .height(1);
 
-    // Rows - build cells dynamically based on visible columns
+    // Rows - build cells dynamically based on visible columns, only for the scrolled window
+    let visible_rows = inner.height.saturating_sub(1) as usize;
     let rows: Vec<Row> = repos
         .iter()
         .enumerate()
+        .skip(app.scroll_offset)
+        .take(visible_rows)
         .map(|(idx, repo)| {
             let is_selected = idx == app.selected;
             let is_marked = app.is_marked(&repo.id);
@@ -131,23 +230,9 @@ fn draw_repos_table(f: &mut Frame, area: Rect, app: &App) {
                 (false, false) => Style::default(),
             };
 
-            let cells: Vec<Cell> = columns.iter().map(|col| {
-                match col {
-                    Column::Origin => {
-                        let mark = if is_marked { "*" } else { "" };
-                        Cell::from(format!("{}{}", mark, format_origin(repo)))
-                    }
-                    Column::Repository => Cell::from(format_repo_name(repo)),
-                    Column::Type => Cell::from(format_type(repo)),
-                    Column::Updated => Cell::from(format_updated(repo)),
-                    Column::Archived => Cell::from(format_archived(repo)),
-                    Column::Private => Cell::from(format_private(repo)),
-                    Column::Ghq => Cell::from(format_ghq(repo, app)),
-                    Column::Status => Cell::from(format_status(repo)),
-                    Column::Dirty => Cell::from(format_dirty(repo)),
-                    Column::Path => Cell::from(format_path(repo)),
-                }
-            }).collect();
+            let cells: Vec<Cell> = columns.iter()
+                .map(|col| Cell::from(format_cell(*col, repo, app)))
+                .collect();
 
             Row::new(cells).style(row_style)
         })
@@ -168,40 +253,49 @@ fn draw_gists_table(f: &mut Frame, area: Rect, app: &App) {
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    if app.gists.is_empty() {
-        let empty = Paragraph::new("No gists found. Press 'r' to refresh.")
+    let gists = app.visible_gists();
+    if gists.is_empty() {
+        let text = if app.gists.is_empty() {
+            "No gists found. Press 'r' to refresh."
+        } else {
+            "No gists match the search. Press Esc to clear it."
+        };
+        let empty = Paragraph::new(text)
             .style(Style::default().fg(Color::DarkGray));
         f.render_widget(empty, inner);
         return;
     }
 
-    // Column widths - pack left
-    let widths = [
-        Constraint::Min(30),     // Description
-        Constraint::Length(6),   // Files
-        Constraint::Length(7),   // Public
-        Constraint::Length(3),   // Dirty
-        Constraint::Length(10),  // Status
-        Constraint::Length(25),  // Path
-    ];
+    let columns = app.visible_gist_columns();
 
-    // Header
-    let header = Row::new(vec![
-        Cell::from("Description").style(Style::default().add_modifier(Modifier::BOLD)),
-        Cell::from("Files").style(Style::default().add_modifier(Modifier::BOLD)),
-        Cell::from("Public").style(Style::default().add_modifier(Modifier::BOLD)),
-        Cell::from("").style(Style::default().add_modifier(Modifier::BOLD)), // Dirty
-        Cell::from("Status").style(Style::default().add_modifier(Modifier::BOLD)),
-        Cell::from("Path").style(Style::default().add_modifier(Modifier::BOLD)),
-    ])
-    .style(Style::default().fg(Color::Cyan))
-    .height(1);
+    // Build widths dynamically based on visible columns
+    let widths: Vec<Constraint> = columns.iter().map(|col| {
+        match col.width() {
+            0 => Constraint::Min(30), // Description takes remainder
+            w => Constraint::Length(w),
+        }
+    }).collect();
+
+    let header_cells: Vec<Cell> = columns.iter().enumerate().map(|(idx, col)| {
+        let style = if idx == app.selected_gist_column {
+            Style::default().add_modifier(Modifier::BOLD).add_modifier(Modifier::UNDERLINED)
+        } else {
+            Style::default().add_modifier(Modifier::BOLD)
+        };
+        Cell::from(format_gist_header(col.name(), *col, app)).style(style)
+    }).collect();
+
+    let header = Row::new(header_cells)
+        .style(Style::default().fg(Color::Cyan))
+        .height(1);
 
     // Rows
-    let rows: Vec<Row> = app
-        .gists
+    let visible_rows = inner.height.saturating_sub(1) as usize;
+    let rows: Vec<Row> = gists
         .iter()
         .enumerate()
+        .skip(app.scroll_offset)
+        .take(visible_rows)
         .map(|(idx, gist)| {
             let is_selected = idx == app.selected;
             let is_marked = app.is_marked(&gist.id);
@@ -212,23 +306,11 @@ fn draw_gists_table(f: &mut Frame, area: Rect, app: &App) {
                 (false, false) => Style::default(),
             };
 
-            let desc_cell = if is_marked {
-                Cell::from(Line::from(vec![
-                    Span::styled("*", Style::default().fg(Color::Magenta)),
-                    format_gist_description(gist),
-                ]))
-            } else {
-                Cell::from(format_gist_description(gist))
-            };
-            Row::new(vec![
-                desc_cell,
-                Cell::from(format!("{}", gist.file_names.len())),
-                Cell::from(if gist.is_public { "✓" } else { "" }),
-                Cell::from(format_gist_dirty(gist)),
-                Cell::from(format_gist_status(gist)),
-                Cell::from(format_gist_local(gist)),
-            ])
-            .style(row_style)
+            let cells: Vec<Cell> = columns.iter()
+                .map(|col| Cell::from(format_gist_cell(*col, gist, app)))
+                .collect();
+
+            Row::new(cells).style(row_style)
         })
         .collect();
 
@@ -256,8 +338,12 @@ fn format_origin(repo: &RepoRow) -> Span<'static> {
     }
 }
 
-fn format_repo_name(repo: &RepoRow) -> Span<'static> {
-    let name = truncate(&repo.name, 19);
+fn format_repo_name(repo: &RepoRow, pinned: bool) -> Line<'static> {
+    let name = if pinned {
+        truncate(&repo.name, 17)
+    } else {
+        truncate(&repo.name, 19)
+    };
     let style = if repo.is_local_only() {
         Style::default().fg(Color::Blue)
     } else if repo.has_local() {
@@ -267,7 +353,15 @@ fn format_repo_name(repo: &RepoRow) -> Span<'static> {
         // Remote only - normal (we don't have it)
         Style::default()
     };
-    Span::styled(name, style)
+    let mut spans = vec![];
+    if pinned {
+        spans.push(Span::styled("★ ", Style::default().fg(Color::Yellow)));
+    }
+    spans.push(Span::styled(name, style));
+    if repo.is_watched {
+        spans.push(Span::styled(" ◉", Style::default().fg(Color::Cyan)));
+    }
+    Line::from(spans)
 }
 
 fn format_type(repo: &RepoRow) -> Line<'static> {
@@ -339,6 +433,21 @@ fn format_private(repo: &RepoRow) -> Span<'static> {
     }
 }
 
+fn format_starred(repo: &RepoRow) -> Span<'static> {
+    if repo.is_starred {
+        Span::styled("★", Style::default().fg(Color::Yellow))
+    } else {
+        Span::raw("")
+    }
+}
+
+fn format_note(repo: &RepoRow) -> Span<'static> {
+    match repo.note {
+        Some(ref note) => Span::styled(truncate(note, 22), Style::default().fg(Color::Magenta)),
+        None => Span::raw(""),
+    }
+}
+
 fn format_archived(repo: &RepoRow) -> Span<'static> {
     if repo.is_archived {
         Span::styled("📦", Style::default().fg(Color::DarkGray))
@@ -360,15 +469,27 @@ fn format_dirty(repo: &RepoRow) -> Span<'static> {
 }
 
 fn format_ghq(repo: &RepoRow, app: &App) -> Span<'static> {
-    match repo.follows_ghq(&app.local_root) {
+    match repo.follows_ghq(&app.scan_roots, app.config.ghq_check()) {
         Some(true) => Span::styled("✓", Style::default().fg(Color::Green)),
         Some(false) => Span::styled("✗", Style::default().fg(Color::Red)),
         None => Span::raw(""), // No local or no GitHub info
     }
 }
 
-fn format_updated(repo: &RepoRow) -> Span<'static> {
-    match repo.last_commit_time {
+fn format_commit_hash(repo: &RepoRow) -> Span<'static> {
+    match repo.last_commit_hash {
+        Some(ref hash) => Span::styled(hash.clone(), Style::default().fg(Color::Yellow)),
+        None => Span::styled("-", Style::default().fg(Color::DarkGray)),
+    }
+}
+
+fn format_updated(repo: &RepoRow, colors: &UpdatedConfig) -> Span<'static> {
+    format_age(repo.last_commit_time, colors)
+}
+
+/// Relative age of a Unix timestamp, colored by the Updated breakpoints
+fn format_age(timestamp: Option<i64>, colors: &UpdatedConfig) -> Span<'static> {
+    match timestamp {
         Some(timestamp) => {
             let now = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -382,7 +503,8 @@ fn format_updated(repo: &RepoRow) -> Span<'static> {
                 format!("{}m ago", diff_secs / 60)
             } else if diff_secs < 86400 {
                 format!("{}h ago", diff_secs / 3600)
-            } else if diff_secs < 604800 {
+            } else if diff_secs < 604800 || colors.fine {
+                // Fine mode never rounds up past days
                 format!("{}d ago", diff_secs / 86400)
             } else if diff_secs < 2592000 {
                 format!("{}w ago", diff_secs / 604800)
@@ -392,13 +514,10 @@ fn format_updated(repo: &RepoRow) -> Span<'static> {
                 format!("{}y ago", diff_secs / 31536000)
             };
 
-            let color = if diff_secs < 86400 {
-                Color::Green  // < 1 day
-            } else if diff_secs < 604800 {
-                Color::Yellow // < 1 week
-            } else {
-                Color::DarkGray // older
-            };
+            // Unknown color names fall back to the default grey
+            let color = colors.color_for_age(diff_secs)
+                .parse::<Color>()
+                .unwrap_or(Color::DarkGray);
 
             Span::styled(text, Style::default().fg(color))
         }
@@ -407,6 +526,28 @@ fn format_updated(repo: &RepoRow) -> Span<'static> {
 }
 
 fn format_status(repo: &RepoRow) -> Span<'static> {
+    let span = format_sync_status(repo);
+    let mut prefix = String::new();
+    if repo.is_shallow {
+        prefix.push('◌');
+    }
+    if repo.is_off_default_branch() {
+        prefix.push('⎇');
+    }
+    if prefix.is_empty() {
+        span
+    } else {
+        Span::styled(format!("{}{}", prefix, span.content), span.style)
+    }
+}
+
+fn format_sync_status(repo: &RepoRow) -> Span<'static> {
+    // Empty remotes have no branch to track; only local commits make the status meaningful
+    let has_local_commits = repo.git_status.as_ref().map(|s| s.has_commits).unwrap_or(false);
+    if repo.is_empty && !has_local_commits {
+        return Span::styled("∅ empty", Style::default().fg(Color::DarkGray));
+    }
+
     match &repo.git_status {
         Some(status) => {
             if !status.has_remote {
@@ -517,6 +658,13 @@ fn format_gist_status(gist: &GistRow) -> Span<'static> {
     }
 }
 
+fn format_gist_updated(gist: &GistRow, colors: &UpdatedConfig) -> Span<'static> {
+    let timestamp = gist.updated_at.as_deref()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .map(|t| t.timestamp());
+    format_age(timestamp, colors)
+}
+
 fn format_gist_local(gist: &GistRow) -> Span<'static> {
     match &gist.local_path {
         Some(path) => {
@@ -527,6 +675,108 @@ fn format_gist_local(gist: &GistRow) -> Span<'static> {
     }
 }
 
+/// Rendered text of one gists table cell
+fn format_gist_cell(column: GistColumn, gist: &GistRow, app: &App) -> Line<'static> {
+    match column {
+        GistColumn::Description if app.is_marked(&gist.id) => Line::from(vec![
+            Span::styled("*", Style::default().fg(Color::Magenta)),
+            format_gist_description(gist),
+        ]),
+        GistColumn::Description => format_gist_description(gist).into(),
+        GistColumn::Files => Line::from(format!("{}", gist.file_names.len())),
+        GistColumn::Public => Line::from(if gist.is_public { "✓" } else { "" }),
+        GistColumn::Dirty => format_gist_dirty(gist).into(),
+        GistColumn::Status => format_gist_status(gist).into(),
+        GistColumn::Updated => format_gist_updated(gist, &app.config.updated).into(),
+        GistColumn::Path => format_gist_local(gist).into(),
+    }
+}
+
+/// Rendered text of one repos table cell
+fn format_cell(column: Column, repo: &RepoRow, app: &App) -> Line<'static> {
+    match column {
+        Column::Origin => {
+            let mark = if app.is_marked(&repo.id) { "*" } else { "" };
+            Line::from(format!("{}{}", mark, format_origin(repo)))
+        }
+        Column::Repository => format_repo_name(repo, app.config.pinned.contains(&repo.id)),
+        Column::Type => format_type(repo),
+        Column::Updated if app.show_commit_hash => format_commit_hash(repo).into(),
+        Column::Updated => format_updated(repo, &app.config.updated).into(),
+        Column::Archived => format_archived(repo).into(),
+        Column::Private => format_private(repo).into(),
+        Column::Starred => format_starred(repo).into(),
+        Column::Ghq => format_ghq(repo, app).into(),
+        Column::Status => format_status(repo).into(),
+        Column::Dirty => format_dirty(repo).into(),
+        Column::Note => format_note(repo).into(),
+        Column::Path => format_path(repo).into(),
+    }
+}
+
+/// Measure the widest rendered value per column among visible rows.
+/// The header (with sort arrow) sets the minimum; Path keeps filling the remainder.
+pub fn fit_column_widths(app: &App) -> HashMap<Column, u16> {
+    let repos = app.visible_repos();
+    app.visible_columns()
+        .iter()
+        .filter(|col| **col != Column::Path)
+        .map(|col| {
+            let header = Line::from(format!("[{} ▲]", col.name())).width();
+            let widest = repos.iter()
+                .map(|repo| format_cell(*col, repo, app).width())
+                .max()
+                .unwrap_or(0);
+            // +1 leaves a gap before the next column
+            let width = (header.max(widest) + 1).min(MAX_FIT_WIDTH) as u16;
+            (*col, width)
+        })
+        .collect()
+}
+
+const MAX_FIT_WIDTH: usize = 40;
+
+/// Visible rows of the current table as tab-separated plain text, header first
+pub fn table_tsv(app: &App) -> String {
+    fn plain(line: Line) -> String {
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        text.replace(['\t', '\n'], " ")
+    }
+    // The * mark is a screen cue, not part of the data (Dirty keeps its own *)
+    fn unmarked(text: String, has_mark: bool) -> String {
+        match text.strip_prefix('*') {
+            Some(rest) if has_mark => rest.to_string(),
+            _ => text,
+        }
+    }
+    let mut lines = Vec::new();
+    match app.view_mode {
+        ViewMode::Repos | ViewMode::Starred => {
+            let columns = app.visible_columns();
+            lines.push(columns.iter().map(|c| c.name()).collect::<Vec<_>>().join("\t"));
+            for repo in app.visible_repos() {
+                let marked = app.is_marked(&repo.id);
+                let cells: Vec<String> = columns.iter()
+                    .map(|c| unmarked(plain(format_cell(*c, repo, app)), marked && *c == Column::Origin))
+                    .collect();
+                lines.push(cells.join("\t"));
+            }
+        }
+        ViewMode::Gists => {
+            let columns = app.visible_gist_columns();
+            lines.push(columns.iter().map(|c| c.name()).collect::<Vec<_>>().join("\t"));
+            for gist in app.visible_gists() {
+                let marked = app.is_marked(&gist.id);
+                let cells: Vec<String> = columns.iter()
+                    .map(|c| unmarked(plain(format_gist_cell(*c, gist, app)), marked && *c == GistColumn::Description))
+                    .collect();
+                lines.push(cells.join("\t"));
+            }
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
 // Format column header with sort indicator
 fn format_header(name: &str, column: SortColumn, app: &App) -> String {
     if app.sort_column == column {
@@ -537,6 +787,22 @@ fn format_header(name: &str, column: SortColumn, app: &App) -> String {
     }
 }
 
+/// Gist header label; a hidden sort column shows its arrow on Description
+fn format_gist_header(name: &str, column: GistColumn, app: &App) -> String {
+    let arrow = if app.gist_sort_ascending { "▲" } else { "▼" };
+    let sort_col = GistSortColumn::from_column(column);
+    if sort_col == Some(app.gist_sort_column) {
+        format!("[{} {}]", name, arrow)
+    } else if column == GistColumn::Description
+        && !app.visible_gist_columns().iter().any(|&c| GistSortColumn::from_column(c) == Some(app.gist_sort_column))
+    {
+        // Sorted by a hidden column; say so on the first header
+        format!("{} [{} {}]", name, app.gist_sort_column.as_str(), arrow)
+    } else {
+        name.to_string()
+    }
+}
+
 // Utility functions
 fn truncate(s: &str, max_len: usize) -> String {
     if s.chars().count() <= max_len {
@@ -550,10 +816,16 @@ fn truncate(s: &str, max_len: usize) -> String {
 fn shorten_path(path: &str) -> String {
     // Replace home directory with ~
     let home = std::env::var("HOME").unwrap_or_default();
-    if !home.is_empty() && path.starts_with(&home) {
-        format!("~{}", &path[home.len()..])
-    } else {
-        path.to_string()
+    shorten_home(path, &home)
+}
+
+/// Replace a leading `home` with ~ without slicing inside a multibyte character.
+/// Only strips at a separator, so /home/foobar is left alone when home is /home/foo.
+fn shorten_home(path: &str, home: &str) -> String {
+    let home = home.trim_end_matches('/');
+    match path.strip_prefix(home) {
+        Some(rest) if !home.is_empty() && (rest.is_empty() || rest.starts_with('/')) => format!("~{}", rest),
+        _ => path.to_string(),
     }
 }
 
@@ -572,11 +844,14 @@ fn draw_status_bar(f: &mut Frame, area: Rect, app: &App) {
             ("✓ ".to_string(), Color::Green, Color::Yellow)
         };
 
-        let status_line = Line::from(vec![
+        let mut spans = vec![
             Span::styled(icon, Style::default().fg(icon_color)),
             Span::styled(msg.clone(), Style::default().fg(text_color)),
-        ]);
-        f.render_widget(Paragraph::new(status_line), area);
+        ];
+        if app.status_is_loading && !app.progress.is_empty() {
+            spans.push(Span::styled(format_progress(app), Style::default().fg(Color::Cyan)));
+        }
+        f.render_widget(Paragraph::new(Line::from(spans)), area);
         return;
     }
 
@@ -585,6 +860,13 @@ fn draw_status_bar(f: &mut Frame, area: Rect, app: &App) {
         let help = match popup.popup_type {
             PopupType::Details => "Enter/Esc: close",
             PopupType::Ignored => "j/k/↑/↓: select │ Enter: unhide │ Esc: close",
+            PopupType::Files => "j/k/↑/↓: select │ Space: stage/unstage │ c: commit staged │ Esc: close",
+            PopupType::Branches => "j/k/↑/↓: select │ n: new │ d: delete │ D: force delete │ Esc: close",
+            PopupType::Confirm => "y/Enter: confirm │ Esc: cancel",
+            PopupType::Copy => "press a key to copy │ Esc: close",
+            PopupType::Help if popup.filter.is_some() => "type to filter │ Backspace: delete │ Esc: clear filter",
+            PopupType::Help => "/: filter │ j/k/↑/↓: scroll │ y: copy │ Esc: close",
+            PopupType::ForkDiff => "j/k/↑/↓: scroll │ r: rebase onto upstream │ y: copy │ Esc: close",
             _ => "j/k/↑/↓: scroll │ y: copy │ Esc: close",
         };
         f.render_widget(
@@ -594,15 +876,41 @@ fn draw_status_bar(f: &mut Frame, area: Rect, app: &App) {
         return;
     }
 
+    // Search mode - show the query being typed
+    if app.input_mode == InputMode::Search {
+        let line = Line::from(vec![
+            Span::styled("/", Style::default().fg(Color::Cyan)),
+            Span::styled(format!("{}█", app.search_query), Style::default().fg(Color::Yellow)),
+            Span::styled(
+                format!("  {} matches │ Enter: keep │ Esc: clear", app.visible_list_len()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]);
+        f.render_widget(Paragraph::new(line), area);
+        return;
+    }
+
     // Build hotkey lines based on current selection
     let (line1, line2) = match app.view_mode {
-        ViewMode::Repos => build_repos_hotkeys(app),
+        ViewMode::Repos | ViewMode::Starred => build_repos_hotkeys(app),
         ViewMode::Gists => build_gists_hotkeys(app),
     };
 
     f.render_widget(Paragraph::new(vec![line1, line2]), area);
 }
 
+/// " 43%" for one running operation, " pull foo 43% · clone bar 10%" for several
+fn format_progress(app: &App) -> String {
+    if app.progress.len() == 1 {
+        let pct = app.progress.values().next().copied().unwrap_or(0);
+        return format!(" {}%", pct);
+    }
+    let mut entries: Vec<_> = app.progress.iter().collect();
+    entries.sort();
+    let parts: Vec<String> = entries.iter().map(|(op, pct)| format!("{} {}%", op, pct)).collect();
+    format!(" {}", parts.join(" · "))
+}
+
 /// Helper to create a hotkey span (enabled or disabled)
 fn hotkey(key: &str, desc: &str, enabled: bool) -> Vec<Span<'static>> {
     let style = if enabled {
@@ -625,7 +933,12 @@ fn build_repos_hotkeys(app: &App) -> (Line<'static>, Line<'static>) {
     let is_dirty = repo.and_then(|r| r.git_status.as_ref()).map(|s| s.is_dirty()).unwrap_or(false);
     let can_change = repo.map(|r| app.can_change_visibility(r)).unwrap_or(false);
     let has_github = repo.map(|r| r.github_url.is_some()).unwrap_or(false);
-    let needs_ghq = repo.map(|r| r.follows_ghq(&app.local_root) == Some(false)).unwrap_or(false);
+    let has_git = repo.map(|r| r.has_git).unwrap_or(false);
+    let is_archived = repo.map(|r| r.is_archived).unwrap_or(false);
+    let is_shallow = repo.map(|r| r.is_shallow).unwrap_or(false);
+    let is_fork = repo.map(|r| r.is_fork && r.fork_parent.is_some()).unwrap_or(false);
+    let needs_ghq = repo.map(|r| r.follows_ghq(&app.scan_roots, app.config.ghq_check()) == Some(false)).unwrap_or(false);
+    let needs_rename = repo.map(|r| r.renamed_folder_path().is_some()).unwrap_or(false);
 
     // Error indicator and mark count
     let mut spans1: Vec<Span> = vec![];
@@ -643,24 +956,43 @@ fn build_repos_hotkeys(app: &App) -> (Line<'static>, Line<'static>) {
     spans1.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
     spans1.extend(hotkey("n", "clone", is_remote_only));
     spans1.extend(hotkey("l", "pull", has_local && !is_dirty));
-    spans1.extend(hotkey("h", "push", has_local && !is_dirty));
-    spans1.extend(hotkey("s", "sync", has_local && !is_dirty));
-    spans1.extend(hotkey("y", "qsync", has_local));
+    spans1.extend(hotkey("h", "push", has_local && !is_dirty && !is_archived));
+    spans1.extend(hotkey("s", "sync", has_local && !is_dirty && !is_archived));
+    spans1.extend(hotkey("y", "qsync", has_local && !is_archived));
+    spans1.extend(hotkey("c", "commit", has_local && has_git));
+    spans1.extend(hotkey("C", "files", has_local && has_git));
+    spans1.extend(hotkey("H", "log", has_local && has_git));
+    spans1.extend(hotkey("F", "fork diff", is_fork));
+    spans1.extend(hotkey("Y", "copy", true));
+    spans1.extend(hotkey("U", "unshallow", is_shallow));
+    spans1.extend(hotkey("B", "branches", has_local && has_git));
     spans1.extend(hotkey("g", "git", has_local));
 
     // Line 2: Repo actions + filters
     let mut spans2: Vec<Span> = vec![];
     spans2.extend(hotkey("p", "priv", can_change));
-    spans2.extend(hotkey("a", "arch", can_change));
+    spans2.extend(hotkey("a", if is_archived { "unarch" } else { "arch" }, can_change));
     spans2.extend(hotkey("o", "web", has_github));
+    spans2.extend(hotkey("W", "watch", has_github));
+    spans2.extend(hotkey("S", "star", has_github));
     spans2.extend(hotkey("O", "files", has_local));
     spans2.extend(hotkey("u", "upload", is_local_only));
+    spans2.extend(hotkey("L", "link", is_local_only));
     spans2.extend(hotkey("z", "ghq", needs_ghq));
+    spans2.extend(hotkey("m", "rename", needs_rename));
     spans2.extend(hotkey("d", "del", has_local));
     spans2.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
     spans2.extend(hotkey("A", "arch", true));
     spans2.extend(hotkey("P", "priv", true));
+    spans2.extend(hotkey("f", "filter", true));
+    spans2.extend(hotkey("R", "uncloned", true));
+    spans2.extend(hotkey("T", "type", true));
+    spans2.extend(hotkey("t", "age", true));
+    spans2.extend(hotkey("M", "mine", true));
     spans2.extend(hotkey("i", "hide", true));
+    spans2.extend(hotkey("b", "pin", true));
+    spans2.extend(hotkey("N", "note", true));
+    spans2.extend(hotkey("#", "noted", true));
     spans2.extend(hotkey("x", "mark", true));
     spans2.extend(hotkey("r", "ref", true));
     spans2.extend(hotkey("?", "help", true));
@@ -690,6 +1022,7 @@ fn build_gists_hotkeys(app: &App) -> (Line<'static>, Line<'static>) {
 
     let mut spans2: Vec<Span> = vec![];
     spans2.extend(hotkey("d", "delete", true));
+    spans2.extend(hotkey("o", "web", true));
     spans2.extend(hotkey("x", "mark", true));
     spans2.extend(hotkey("r", "refresh", true));
     spans2.extend(hotkey("Tab", "repos", true));
@@ -704,17 +1037,40 @@ fn draw_popup(f: &mut Frame, popup: &crate::app::Popup) {
         PopupType::Details => (60, 50),
         PopupType::Ignored => (60, 50),
         PopupType::Errors => (70, 60),
+        PopupType::Files => (60, 60),
+        PopupType::Confirm => (60, 35),
+        PopupType::Log => (75, 70),
+        PopupType::Copy => (60, 25),
+        PopupType::ForkDiff => (75, 70),
+        PopupType::Path => (80, 15),
+        PopupType::Branches => (50, 50),
+        PopupType::DiskUsage => (70, 60),
         PopupType::Upload => return, // Upload form is drawn by draw_upload_form_popup
     };
 
     let area = centered_rect(width, height, f.area());
     f.render_widget(Clear, area);
 
+    let help_title;
     let title = match popup.popup_type {
-        PopupType::Help => " Help ",
+        PopupType::Help => match popup.filter {
+            Some(ref q) => {
+                help_title = format!(" Help /{} ", q);
+                help_title.as_str()
+            }
+            None => " Help ",
+        },
         PopupType::Details => " Details ",
         PopupType::Ignored => " Ignored Repos ",
         PopupType::Errors => " Error Log ",
+        PopupType::Files => " Changed Files ",
+        PopupType::Confirm => " Confirm ",
+        PopupType::Log => " Log ",
+        PopupType::Copy => " Copy ",
+        PopupType::ForkDiff => " Fork vs Upstream ",
+        PopupType::Path => " Path ",
+        PopupType::Branches => " Branches ",
+        PopupType::DiskUsage => " Disk Usage ",
         PopupType::Upload => " Upload ",
     };
 
@@ -728,12 +1084,12 @@ fn draw_popup(f: &mut Frame, popup: &crate::app::Popup) {
 
     // Calculate visible content with scroll
     let visible_height = inner_area.height as usize;
-    let total_lines = popup.content.len();
+    let lines = popup.visible_lines();
+    let total_lines = lines.len();
     let scroll = popup.scroll.min(total_lines.saturating_sub(visible_height));
 
-    let visible_content: Vec<Line> = popup
-        .content
-        .iter()
+    let visible_content: Vec<Line> = lines
+        .into_iter()
         .enumerate()
         .skip(scroll)
         .take(visible_height)
@@ -741,7 +1097,7 @@ fn draw_popup(f: &mut Frame, popup: &crate::app::Popup) {
             if popup.popup_type == PopupType::Help {
                 // Parse styled help content: "KEY|DESCRIPTION|COLOR"
                 format_help_line(s)
-            } else if popup.popup_type == PopupType::Ignored && idx >= 2 {
+            } else if matches!(popup.popup_type, PopupType::Ignored | PopupType::Branches) && idx >= 2 {
                 // Highlight selected item in ignored popup (skip header)
                 if idx == popup.selected {
                     Line::from(Span::styled(
@@ -751,13 +1107,23 @@ fn draw_popup(f: &mut Frame, popup: &crate::app::Popup) {
                 } else {
                     Line::from(format!("  {}", s))
                 }
+            } else if popup.popup_type == PopupType::Files && idx >= 2 {
+                format_file_change_line(s, idx == popup.selected)
+            } else if matches!(popup.popup_type, PopupType::Log | PopupType::ForkDiff) && idx >= 2 {
+                format_log_line(s)
+            } else if popup.popup_type == PopupType::Copy {
+                format_copy_line(s)
             } else {
                 Line::from(s.clone())
             }
         })
         .collect();
 
-    let paragraph = Paragraph::new(visible_content);
+    let mut paragraph = Paragraph::new(visible_content);
+    if popup.popup_type == PopupType::Path {
+        // The whole point is to read a long path, so wrap rather than clip
+        paragraph = paragraph.wrap(Wrap { trim: false });
+    }
     f.render_widget(paragraph, inner_area);
 
     // Draw scrollbar if content overflows
@@ -776,6 +1142,52 @@ fn draw_popup(f: &mut Frame, popup: &crate::app::Popup) {
     }
 }
 
+/// Render a "KEY|LABEL|VALUE" copy menu entry
+fn format_copy_line(s: &str) -> Line<'static> {
+    let mut parts = s.splitn(3, '|');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(key), Some(label), Some(value)) => Line::from(vec![
+            Span::styled(format!(" {} ", key), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::raw(format!("{:<14}", label)),
+            Span::styled(value.to_string(), Style::default().fg(Color::DarkGray)),
+        ]),
+        _ => Line::from(s.to_string()),
+    }
+}
+
+/// Render a "HASH|AUTHOR|TIME|SUBJECT" log entry
+fn format_log_line(s: &str) -> Line<'static> {
+    let mut parts = s.splitn(4, '|');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(hash), Some(author), Some(time), Some(subject)) => Line::from(vec![
+            Span::styled(format!("{} ", hash), Style::default().fg(Color::Cyan)),
+            Span::raw(subject.to_string()),
+            Span::styled(format!("  {}", author), Style::default().fg(Color::DarkGray)),
+            Span::styled(format!(", {}", time), Style::default().fg(Color::Gray)),
+        ]),
+        _ => Line::from(s.to_string()),
+    }
+}
+
+/// Format a "XY path" porcelain line: green when staged, red when only in the worktree
+fn format_file_change_line(s: &str, selected: bool) -> Line<'static> {
+    let index = s.chars().next().unwrap_or(' ');
+    let color = if index != ' ' && index != '?' {
+        Color::Green
+    } else {
+        Color::Red
+    };
+    let marker = if selected { "> " } else { "  " };
+    let mut style = Style::default().fg(color);
+    if selected {
+        style = style.add_modifier(Modifier::BOLD);
+    }
+    Line::from(vec![
+        Span::styled(marker, Style::default().fg(Color::Yellow)),
+        Span::styled(s.to_string(), style),
+    ])
+}
+
 fn format_help_line(s: &str) -> Line<'static> {
     if s.is_empty() {
         return Line::from("");
@@ -833,38 +1245,23 @@ fn format_help_line(s: &str) -> Line<'static> {
     Line::from(spans)
 }
 
-fn draw_confirm_delete_popup(f: &mut Frame, app: &App) {
-    let area = centered_rect(50, 25, f.area());
+/// Draw a red-bordered confirmation; typed dialogs add a prompt and the answer so far
+fn draw_confirm_dialog(f: &mut Frame, dialog: &ConfirmDialog, answer: &str) {
+    let typed = dialog.kind == ConfirmKind::Typed;
+    let chrome: u16 = if typed { 6 } else { 2 }; // Borders, plus prompt and input box
+    let screen = f.area();
+    let width = (screen.width * 3 / 5).max(50).min(screen.width);
+    let height = (dialog.message.len() as u16 + chrome).min(screen.height);
+    let area = Rect::new(
+        screen.x + (screen.width - width) / 2,
+        screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    );
     f.render_widget(Clear, area);
 
-    let marked_count = app.marked_local_repos().len();
-    let (title, warning_text): (&str, String) = match app.pending_delete {
-        Some(DeleteType::LocalRepo) => {
-            if marked_count > 0 {
-                (
-                    " Confirm Delete Marked ",
-                    format!("Type 'y' or 'yes' to delete {} repositories locally:", marked_count),
-                )
-            } else {
-                (
-                    " Confirm Delete Local ",
-                    "Type 'y' or 'yes' to delete this repository locally:".to_string(),
-                )
-            }
-        }
-        Some(DeleteType::RemoteRepo) => (
-            " Confirm Delete Remote ",
-            "Type 'y' or 'yes' to DELETE THIS REPO FROM GITHUB:".to_string(),
-        ),
-        Some(DeleteType::Gist) => (
-            " Confirm Delete Gist ",
-            "Type 'y' or 'yes' to delete this gist from GitHub:".to_string(),
-        ),
-        None => (" Confirm Delete ", "Type 'y' or 'yes' to confirm:".to_string()),
-    };
-
     let block = Block::default()
-        .title(title)
+        .title(dialog.title.as_str())
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Red));
 
@@ -874,19 +1271,41 @@ fn draw_confirm_delete_popup(f: &mut Frame, app: &App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3),
-            Constraint::Length(3),
-            Constraint::Min(1),
+            Constraint::Min(1),                            // Message
+            Constraint::Length(if typed { 1 } else { 0 }), // Prompt
+            Constraint::Length(if typed { 3 } else { 0 }), // Input
         ])
         .split(inner);
 
-    let warning = Paragraph::new(warning_text).style(Style::default().fg(Color::Red));
-    f.render_widget(warning, chunks[0]);
+    // Show as many lines as fit, then summarize the rest
+    let capacity = chunks[0].height as usize;
+    let mut lines: Vec<Line> = Vec::new();
+    for (idx, text) in dialog.message.iter().enumerate() {
+        if idx + 1 == capacity && dialog.message.len() > capacity {
+            lines.push(Line::from(Span::styled(
+                format!("  …and {} more", dialog.message.len() - idx),
+                Style::default().fg(Color::DarkGray),
+            )));
+            break;
+        }
+        let style = if idx == 0 {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(Span::styled(text.clone(), style)));
+    }
+    f.render_widget(Paragraph::new(lines), chunks[0]);
 
-    let input = Paragraph::new(app.confirm_buffer.as_str())
-        .style(Style::default().fg(Color::Red))
-        .block(Block::default().borders(Borders::ALL));
-    f.render_widget(input, chunks[1]);
+    if typed {
+        let prompt = Paragraph::new(dialog.prompt()).style(Style::default().fg(Color::Red));
+        f.render_widget(prompt, chunks[1]);
+
+        let input = Paragraph::new(answer)
+            .style(Style::default().fg(Color::Red))
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(input, chunks[2]);
+    }
 }
 
 fn draw_upload_form_popup(f: &mut Frame, app: &App) {
@@ -914,14 +1333,23 @@ fn draw_upload_form_popup(f: &mut Frame, app: &App) {
             .margin(1)
             .split(inner);
 
-        // Name field
-        let name_style = if form.active_field == UploadField::Name {
+        // Name field (red with the reason while invalid or already taken)
+        let name_error = form.name_error().or_else(|| {
+            app.upload_name_taken().then_some("already exists on GitHub for this owner")
+        });
+        let name_style = if name_error.is_some() {
+            Style::default().fg(Color::Red)
+        } else if form.active_field == UploadField::Name {
             Style::default().fg(Color::Yellow)
         } else {
             Style::default()
         };
+        let name_title = match name_error {
+            Some(error) => format!(" Name: {} ", error),
+            None => " Name ".to_string(),
+        };
         let name_block = Block::default()
-            .title(" Name ")
+            .title(name_title)
             .borders(Borders::ALL)
             .border_style(name_style);
         let name_input = Paragraph::new(form.name.as_str())
@@ -976,14 +1404,185 @@ fn draw_upload_form_popup(f: &mut Frame, app: &App) {
         ]);
         f.render_widget(Paragraph::new(org_line), chunks[3]);
 
-        // Instructions
+        // Instructions (submit is unavailable while the name is invalid)
+        let submit_style = if name_error.is_some() {
+            Style::default().fg(Color::DarkGray).add_modifier(Modifier::CROSSED_OUT)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
         let instr = Line::from(vec![
-            Span::styled("Tab/↓↑: navigate │ Enter: submit │ Esc: cancel", Style::default().fg(Color::DarkGray)),
+            Span::styled("Tab/↓↑: navigate │ ", Style::default().fg(Color::DarkGray)),
+            Span::styled("Enter: submit", submit_style),
+            Span::styled(" │ Esc: cancel", Style::default().fg(Color::DarkGray)),
         ]);
         f.render_widget(Paragraph::new(instr), chunks[4]);
     }
 }
 
+fn draw_commit_popup(f: &mut Frame, app: &App) {
+    let area = centered_rect(50, 35, f.area());
+    f.render_widget(Clear, area);
+
+    let title = app.commit_form
+        .as_ref()
+        .map(|form| format!(" Commit {} ", form.repo_name))
+        .unwrap_or_else(|| " Commit ".to_string());
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if let Some(ref form) = app.commit_form {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Message
+                Constraint::Length(1), // Amend
+                Constraint::Length(1), // Push
+                Constraint::Min(1),    // Instructions
+            ])
+            .margin(1)
+            .split(inner);
+
+        // Message field
+        let message_title = if form.amend {
+            " Message (empty keeps current) "
+        } else {
+            " Message "
+        };
+        let message_block = Block::default()
+            .title(message_title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+        let message_input = Paragraph::new(form.message.as_str())
+            .block(message_block);
+        f.render_widget(message_input, chunks[0]);
+
+        // Amend toggle
+        let amend_line = if form.can_amend {
+            let amend_text = if form.amend { "[x] Amend last commit" } else { "[ ] Amend last commit" };
+            Line::from(vec![
+                Span::styled(amend_text, Style::default()),
+                Span::styled(" (Tab to toggle)", Style::default().fg(Color::DarkGray)),
+            ])
+        } else {
+            Line::from(Span::styled(
+                "[ ] Amend last commit (no commits yet)",
+                Style::default().fg(Color::DarkGray),
+            ))
+        };
+        f.render_widget(Paragraph::new(amend_line), chunks[1]);
+
+        // Push toggle
+        let push_line = if form.amend {
+            Line::from(Span::styled(
+                "[ ] Push after commit (not with amend)",
+                Style::default().fg(Color::DarkGray),
+            ))
+        } else {
+            let push_text = if form.push { "[x] Push after commit" } else { "[ ] Push after commit" };
+            Line::from(vec![
+                Span::styled(push_text, Style::default()),
+                Span::styled(" (Shift+Tab to toggle)", Style::default().fg(Color::DarkGray)),
+            ])
+        };
+        f.render_widget(Paragraph::new(push_line), chunks[2]);
+
+        // Instructions
+        let instr_text = if form.stage_all {
+            "Enter: commit all changes │ Esc: cancel"
+        } else {
+            "Enter: commit staged changes │ Esc: cancel"
+        };
+        let instr = Line::from(vec![
+            Span::styled(instr_text, Style::default().fg(Color::DarkGray)),
+        ]);
+        f.render_widget(Paragraph::new(instr), chunks[3]);
+    }
+}
+
+fn draw_note_popup(f: &mut Frame, app: &App) {
+    let area = centered_rect(50, 20, f.area());
+    f.render_widget(Clear, area);
+
+    let name = app.note_target
+        .as_ref()
+        .and_then(|id| app.repos.iter().chain(app.starred.iter()).find(|r| &r.id == id))
+        .map(|r| r.name.clone())
+        .unwrap_or_default();
+
+    let block = Block::default()
+        .title(format!(" Note for {} ", name))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Note
+            Constraint::Min(1),    // Instructions
+        ])
+        .margin(1)
+        .split(inner);
+
+    let input = Paragraph::new(app.input_buffer.as_str())
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Yellow)));
+    f.render_widget(input, chunks[0]);
+
+    let instr = Line::from(Span::styled(
+        "Enter: save (empty removes) │ Esc: cancel",
+        Style::default().fg(Color::DarkGray),
+    ));
+    f.render_widget(Paragraph::new(instr), chunks[1]);
+}
+
+fn draw_new_branch_popup(f: &mut Frame, app: &App) {
+    let area = centered_rect(50, 20, f.area());
+    f.render_widget(Clear, area);
+
+    let name = app.branch_target.as_ref().map(|(name, _)| name.as_str()).unwrap_or("");
+    let block = Block::default()
+        .title(format!(" New branch in {} ", name))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Branch name
+            Constraint::Min(1),    // Instructions
+        ])
+        .margin(1)
+        .split(inner);
+
+    // Red with the reason while the name is invalid (an empty name is just unfinished)
+    let branch = app.input_buffer.trim();
+    let error = if branch.is_empty() { None } else { git::branch_name_error(branch) };
+    let (title, style) = match error {
+        Some(error) => (format!(" Branch: {} ", error), Style::default().fg(Color::Red)),
+        None => (" Branch ".to_string(), Style::default().fg(Color::Yellow)),
+    };
+    let input = Paragraph::new(app.input_buffer.as_str())
+        .block(Block::default().title(title).borders(Borders::ALL).border_style(style));
+    f.render_widget(input, chunks[0]);
+
+    let instr = Line::from(Span::styled(
+        "Enter: create and switch │ Esc: cancel",
+        Style::default().fg(Color::DarkGray),
+    ));
+    f.render_widget(Paragraph::new(instr), chunks[1]);
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -1003,3 +1602,117 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shorten_home_handles_unicode_and_spaces() {
+        assert_eq!(shorten_home("/home/jösé/my repos/ünï", "/home/jösé"), "~/my repos/ünï");
+        assert_eq!(shorten_home("/home/jö", "/home/jösé"), "/home/jö");
+        assert_eq!(shorten_home("/srv/ünï", ""), "/srv/ünï");
+    }
+
+    #[test]
+    fn shorten_home_only_strips_at_separator() {
+        assert_eq!(shorten_home("/home/foobar/src", "/home/foo"), "/home/foobar/src");
+        assert_eq!(shorten_home("/home/foo/src", "/home/foo"), "~/src");
+        assert_eq!(shorten_home("/home/foo", "/home/foo/"), "~");
+    }
+
+    /// Cloned GitHub repo with a clean, up-to-date working tree
+    fn cloned_repo() -> RepoRow {
+        RepoRow {
+            id: "https://github.com/o/tool".to_string(),
+            owner: Some("o".to_string()),
+            name: "tool".to_string(),
+            github_url: Some("https://github.com/o/tool".to_string()),
+            local_path: Some("/src/github.com/o/tool".to_string()),
+            has_git: true,
+            git_status: Some(git::RepoStatus {
+                branch: "main".to_string(),
+                has_remote: true,
+                has_commits: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn status_mut(repo: &mut RepoRow) -> &mut git::RepoStatus {
+        repo.git_status.as_mut().unwrap()
+    }
+
+    #[test]
+    fn dirty_repo_shows_yellow_star() {
+        let mut repo = cloned_repo();
+        assert_eq!(format_dirty(&repo).content, "");
+
+        status_mut(&mut repo).untracked = 2;
+        let span = format_dirty(&repo);
+        assert_eq!(span.content, "*");
+        assert_eq!(span.style.fg, Some(Color::Yellow));
+        // Dirty wins over ahead/behind in the Status column
+        status_mut(&mut repo).ahead = 1;
+        assert_eq!(format_sync_status(&repo).content, "~");
+    }
+
+    #[test]
+    fn sync_status_text_and_color() {
+        let mut repo = cloned_repo();
+        let span = format_sync_status(&repo);
+        assert_eq!((span.content.as_ref(), span.style.fg), ("✓", Some(Color::Green)));
+
+        status_mut(&mut repo).ahead = 3;
+        status_mut(&mut repo).behind = 2;
+        let span = format_sync_status(&repo);
+        assert_eq!((span.content.as_ref(), span.style.fg), ("⇅ +3/-2", Some(Color::Red)));
+
+        status_mut(&mut repo).behind = 0;
+        let span = format_sync_status(&repo);
+        assert_eq!((span.content.as_ref(), span.style.fg), ("↑ +3", Some(Color::Magenta)));
+
+        status_mut(&mut repo).has_remote = false;
+        let span = format_sync_status(&repo);
+        assert_eq!((span.content.as_ref(), span.style.fg), ("?", Some(Color::Blue)));
+    }
+
+    #[test]
+    fn private_repo_shows_lock() {
+        let mut repo = cloned_repo();
+        assert_eq!(format_private(&repo).content, "");
+        repo.is_private = true;
+        assert_eq!(format_private(&repo).content, "🔒");
+    }
+
+    #[test]
+    fn repo_name_color_follows_location() {
+        let mut repo = cloned_repo();
+        let name_fg = |repo: &RepoRow| format_repo_name(repo, false).spans[0].style.fg;
+        assert_eq!(name_fg(&repo), Some(Color::DarkGray));
+
+        repo.local_path = None;
+        assert_eq!(name_fg(&repo), None);
+
+        repo.local_path = Some("/src/misc/tool".to_string());
+        repo.github_url = None;
+        repo.owner = None;
+        assert_eq!(name_fg(&repo), Some(Color::Blue));
+        assert_eq!(format_origin(&repo).content, "(local)");
+    }
+
+    #[test]
+    fn table_tsv_leaves_out_marks() {
+        let mut repo = cloned_repo();
+        status_mut(&mut repo).untracked = 1;
+        let mut app = crate::app::tests::test_app(vec![repo]);
+        app.toggle_mark();
+
+        let tsv = table_tsv(&app);
+        let row: Vec<&str> = tsv.lines().nth(1).unwrap().split('\t').collect();
+        let column = |c: Column| row[app.visible_columns().iter().position(|v| *v == c).unwrap()];
+        assert_eq!(column(Column::Origin), "o");
+        assert_eq!(column(Column::Dirty), "*");
+    }
+}